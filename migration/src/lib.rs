@@ -5,6 +5,26 @@ mod m20220101_000001_create_wotd_table;
 mod m20230610_033154_create_user_table;
 mod m20230610_040548_create_event_table;
 mod m20230611_214244_add_fuzzystrmatch;
+mod m20261018_000001_create_inflection_table;
+mod m20261018_000002_add_unaccented_lemma_index;
+mod m20261018_000003_add_definition_search_index;
+mod m20261018_000004_create_dictionary_table;
+mod m20261018_000005_add_trigram_index;
+mod m20261018_000006_create_phonetic_table;
+mod m20261018_000007_add_output_format_to_user;
+mod m20261018_000008_create_locution_table;
+mod m20261018_000009_create_abbreviation_table;
+mod m20261018_000010_create_relation_table;
+mod m20261018_000011_add_word_game_keys_to_dle;
+mod m20261018_000012_add_show_syllables_to_user;
+mod m20261018_000013_create_chat_table;
+mod m20261018_000014_add_delivery_settings_to_user;
+mod m20261018_000015_create_wotd_delivery_table;
+mod m20261018_000016_add_image_output_format;
+mod m20261018_000017_add_inline_dictionary_to_user;
+mod m20261018_000018_create_scheduled_job_table;
+mod m20261018_000019_create_wotd_target_table;
+mod m20261018_000020_create_wotd_post_table;
 
 pub struct Migrator;
 
@@ -17,6 +37,26 @@ impl MigratorTrait for Migrator {
             Box::new(m20230610_033154_create_user_table::Migration),
             Box::new(m20230610_040548_create_event_table::Migration),
             Box::new(m20230611_214244_add_fuzzystrmatch::Migration),
+            Box::new(m20261018_000001_create_inflection_table::Migration),
+            Box::new(m20261018_000002_add_unaccented_lemma_index::Migration),
+            Box::new(m20261018_000003_add_definition_search_index::Migration),
+            Box::new(m20261018_000004_create_dictionary_table::Migration),
+            Box::new(m20261018_000005_add_trigram_index::Migration),
+            Box::new(m20261018_000006_create_phonetic_table::Migration),
+            Box::new(m20261018_000007_add_output_format_to_user::Migration),
+            Box::new(m20261018_000008_create_locution_table::Migration),
+            Box::new(m20261018_000009_create_abbreviation_table::Migration),
+            Box::new(m20261018_000010_create_relation_table::Migration),
+            Box::new(m20261018_000011_add_word_game_keys_to_dle::Migration),
+            Box::new(m20261018_000012_add_show_syllables_to_user::Migration),
+            Box::new(m20261018_000013_create_chat_table::Migration),
+            Box::new(m20261018_000014_add_delivery_settings_to_user::Migration),
+            Box::new(m20261018_000015_create_wotd_delivery_table::Migration),
+            Box::new(m20261018_000016_add_image_output_format::Migration),
+            Box::new(m20261018_000017_add_inline_dictionary_to_user::Migration),
+            Box::new(m20261018_000018_create_scheduled_job_table::Migration),
+            Box::new(m20261018_000019_create_wotd_target_table::Migration),
+            Box::new(m20261018_000020_create_wotd_post_table::Migration),
         ]
     }
 }
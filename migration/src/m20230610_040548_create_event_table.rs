@@ -83,6 +83,7 @@ impl MigrationTrait for Migration {
 
 /// Learn more at https://docs.rs/sea-query#iden
 #[derive(Iden)]
+#[allow(clippy::enum_variant_names)]
 enum Event {
     Table,
     Id,
@@ -0,0 +1,36 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Inflected forms (plurals, feminines and conjugated forms) of the lemmas
+        manager
+            .create_table(
+                Table::create()
+                    .table(Inflection::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(Inflection::Form).string().not_null())
+                    .col(ColumnDef::new(Inflection::Lemma).string().not_null())
+                    .primary_key(Index::create().col(Inflection::Form).col(Inflection::Lemma))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Inflection::Table).to_owned())
+            .await
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+enum Inflection {
+    Table,
+    Form,
+    Lemma,
+}
@@ -0,0 +1,72 @@
+use sea_orm_migration::{
+    prelude::*,
+    sea_orm::{DbBackend, Statement},
+};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db_conn = manager.get_connection();
+
+        // Lowercase and remove the written accents, but keep the «ñ» which is a
+        // different letter and not an accented «n» (año / ano). Unlike `unaccent`
+        // from the extension of the same name, this is IMMUTABLE and can be indexed.
+        db_conn
+            .execute(Statement::from_string(
+                DbBackend::Postgres,
+                r#"CREATE FUNCTION unaccent_lemma(text) RETURNS text AS $$
+                    SELECT translate(lower($1), 'áéíóúàèìòùäëïöüâêîôû', 'aeiouaeiouaeiouaeiou')
+                $$ LANGUAGE SQL IMMUTABLE STRICT PARALLEL SAFE"#
+                    .to_string(),
+            ))
+            .await?;
+
+        db_conn
+            .execute(Statement::from_string(
+                DbBackend::Postgres,
+                r#"CREATE INDEX "dle_lemma_unaccented_idx" ON "dle" (unaccent_lemma("lemma"))"#
+                    .to_string(),
+            ))
+            .await?;
+
+        db_conn
+            .execute(Statement::from_string(
+                DbBackend::Postgres,
+                r#"CREATE INDEX "inflection_form_unaccented_idx" ON "inflection" (unaccent_lemma("form"))"#
+                    .to_string(),
+            ))
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db_conn = manager.get_connection();
+
+        db_conn
+            .execute(Statement::from_string(
+                DbBackend::Postgres,
+                r#"DROP INDEX "inflection_form_unaccented_idx""#.to_string(),
+            ))
+            .await?;
+
+        db_conn
+            .execute(Statement::from_string(
+                DbBackend::Postgres,
+                r#"DROP INDEX "dle_lemma_unaccented_idx""#.to_string(),
+            ))
+            .await?;
+
+        db_conn
+            .execute(Statement::from_string(
+                DbBackend::Postgres,
+                r#"DROP FUNCTION unaccent_lemma(text)"#.to_string(),
+            ))
+            .await?;
+
+        Ok(())
+    }
+}
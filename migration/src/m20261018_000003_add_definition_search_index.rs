@@ -0,0 +1,38 @@
+use sea_orm_migration::{
+    prelude::*,
+    sea_orm::{DbBackend, Statement},
+};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db_conn = manager.get_connection();
+
+        // Full text search over the definitions for the reverse dictionary
+        db_conn
+            .execute(Statement::from_string(
+                DbBackend::Postgres,
+                r#"CREATE INDEX "dle_definition_fts_idx" ON "dle" USING GIN (to_tsvector('spanish', "definition"))"#
+                    .to_string(),
+            ))
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db_conn = manager.get_connection();
+
+        db_conn
+            .execute(Statement::from_string(
+                DbBackend::Postgres,
+                r#"DROP INDEX "dle_definition_fts_idx""#.to_string(),
+            ))
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,165 @@
+use sea_orm_migration::{
+    prelude::*,
+    sea_orm::{DbBackend, Statement},
+};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Dictionaries the entries of the "dle" table come from
+        manager
+            .create_table(
+                Table::create()
+                    .table(Dictionary::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Dictionary::Code)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Dictionary::Name).string().not_null())
+                    .col(ColumnDef::new(Dictionary::ShortName).string().not_null())
+                    .col(ColumnDef::new(Dictionary::Url).string().null())
+                    .col(
+                        ColumnDef::new(Dictionary::Priority)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .exec_stmt(
+                Query::insert()
+                    .into_table(Dictionary::Table)
+                    .columns([
+                        Dictionary::Code,
+                        Dictionary::Name,
+                        Dictionary::ShortName,
+                        Dictionary::Url,
+                        Dictionary::Priority,
+                    ])
+                    .values_panic([
+                        "dle".into(),
+                        "Diccionario de la lengua española".into(),
+                        "DLE".into(),
+                        Some("https://dle.rae.es/{}").into(),
+                        0.into(),
+                    ])
+                    .values_panic([
+                        "damer".into(),
+                        "Diccionario de americanismos".into(),
+                        "DAMER".into(),
+                        Some("https://www.asale.org/damer/{}").into(),
+                        1.into(),
+                    ])
+                    .values_panic([
+                        "dpd".into(),
+                        "Diccionario panhispánico de dudas".into(),
+                        "DPD".into(),
+                        Some("https://www.rae.es/dpd/{}").into(),
+                        2.into(),
+                    ])
+                    .values_panic([
+                        "glosario".into(),
+                        "Glosario".into(),
+                        "Glosario".into(),
+                        Option::<String>::None.into(),
+                        3.into(),
+                    ])
+                    .to_owned(),
+            )
+            .await?;
+
+        // Every entry belongs to a dictionary, existing ones to the DLE
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Dle::Table)
+                    .add_column(
+                        ColumnDef::new(Dle::Source)
+                            .string()
+                            .not_null()
+                            .default("dle"),
+                    )
+                    .add_foreign_key(
+                        TableForeignKey::new()
+                            .name("dle_source_fkey")
+                            .from_tbl(Dle::Table)
+                            .from_col(Dle::Source)
+                            .to_tbl(Dictionary::Table)
+                            .to_col(Dictionary::Code)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // The same lemma can be defined by several dictionaries
+        manager
+            .get_connection()
+            .execute(Statement::from_string(
+                DbBackend::Postgres,
+                r#"ALTER TABLE "dle" DROP CONSTRAINT "dle_pkey", ADD PRIMARY KEY ("lemma", "source")"#
+                    .to_string(),
+            ))
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db_conn = manager.get_connection();
+
+        db_conn
+            .execute(Statement::from_string(
+                DbBackend::Postgres,
+                r#"DELETE FROM "dle" WHERE "source" <> 'dle'"#.to_string(),
+            ))
+            .await?;
+
+        db_conn
+            .execute(Statement::from_string(
+                DbBackend::Postgres,
+                r#"ALTER TABLE "dle" DROP CONSTRAINT "dle_pkey", ADD PRIMARY KEY ("lemma")"#
+                    .to_string(),
+            ))
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Dle::Table)
+                    .drop_column(Dle::Source)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(Dictionary::Table).to_owned())
+            .await
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+enum Dictionary {
+    Table,
+    Code,
+    Name,
+    ShortName,
+    Url,
+    Priority,
+}
+
+#[derive(Iden)]
+enum Dle {
+    Table,
+    Source,
+}
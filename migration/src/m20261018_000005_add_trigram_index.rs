@@ -0,0 +1,47 @@
+use sea_orm_migration::{
+    prelude::*,
+    sea_orm::{DbBackend, Statement},
+};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db_conn = manager.get_connection();
+
+        db_conn
+            .execute(Statement::from_string(
+                DbBackend::Postgres,
+                r#"CREATE EXTENSION IF NOT EXISTS pg_trgm"#.to_string(),
+            ))
+            .await?;
+
+        // Trigram index for the fuzzy search of lemmas
+        db_conn
+            .execute(Statement::from_string(
+                DbBackend::Postgres,
+                r#"CREATE INDEX "dle_lemma_trgm_idx" ON "dle" USING GIN (unaccent_lemma("lemma") gin_trgm_ops)"#
+                    .to_string(),
+            ))
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db_conn = manager.get_connection();
+
+        // pg_trgm may predate this migration or be used by other objects, so
+        // only the index is dropped
+        db_conn
+            .execute(Statement::from_string(
+                DbBackend::Postgres,
+                r#"DROP INDEX "dle_lemma_trgm_idx""#.to_string(),
+            ))
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,50 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Spanish phonetic key of every lemma, to find homophones
+        manager
+            .create_table(
+                Table::create()
+                    .table(Phonetic::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Phonetic::Lemma)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Phonetic::Key).string().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("phonetic_key_idx")
+                    .table(Phonetic::Table)
+                    .col(Phonetic::Key)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Phonetic::Table).to_owned())
+            .await
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+enum Phonetic {
+    Table,
+    Lemma,
+    Key,
+}
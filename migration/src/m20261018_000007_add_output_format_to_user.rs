@@ -0,0 +1,72 @@
+use sea_orm_migration::prelude::*;
+use sea_query::extension::postgres::Type;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // How definitions are sent to each user
+        manager
+            .create_type(
+                Type::create()
+                    .as_enum(OutputFormat::Table)
+                    .values([OutputFormat::Full, OutputFormat::Compact])
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(User::Table)
+                    .add_column(
+                        ColumnDef::new(User::OutputFormat)
+                            .enumeration(
+                                OutputFormat::Table,
+                                [OutputFormat::Full, OutputFormat::Compact],
+                            )
+                            .not_null()
+                            .default("full"),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(User::Table)
+                    .drop_column(User::OutputFormat)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_type(
+                Type::drop()
+                    .if_exists()
+                    .name(OutputFormat::Table)
+                    .restrict()
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+enum User {
+    Table,
+    OutputFormat,
+}
+
+#[derive(Iden)]
+enum OutputFormat {
+    Table, // Not really a Table but better than hardcoding the Iden impl by hand
+    Full,
+    Compact,
+}
@@ -0,0 +1,59 @@
+use sea_orm_migration::{
+    prelude::*,
+    sea_orm::{DbBackend, Statement},
+};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Locutions defined inside the entry of another lemma
+        manager
+            .create_table(
+                Table::create()
+                    .table(Locution::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(Locution::Locution).string().not_null())
+                    .col(ColumnDef::new(Locution::Lemma).string().not_null())
+                    .col(ColumnDef::new(Locution::Source).string().not_null())
+                    .primary_key(
+                        Index::create()
+                            .col(Locution::Locution)
+                            .col(Locution::Lemma)
+                            .col(Locution::Source),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Used both for exact and prefix matches
+        manager
+            .get_connection()
+            .execute(Statement::from_string(
+                DbBackend::Postgres,
+                r#"CREATE INDEX "locution_locution_unaccented_idx" ON "locution" (unaccent_lemma("locution") text_pattern_ops)"#
+                    .to_string(),
+            ))
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Locution::Table).to_owned())
+            .await
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+#[allow(clippy::enum_variant_names)]
+enum Locution {
+    Table,
+    Locution,
+    Lemma,
+    Source,
+}
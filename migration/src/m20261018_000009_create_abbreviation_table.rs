@@ -0,0 +1,141 @@
+use sea_orm_migration::prelude::*;
+
+/// Abbreviations used in the definitions of the DLE
+const ABBREVIATIONS: [(&str, &str); 87] = [
+    ("abl.", "ablativo"),
+    ("abrev.", "abreviación"),
+    ("acep.", "acepción"),
+    ("acus.", "acusativo"),
+    ("adj.", "adjetivo"),
+    ("adv.", "adverbio"),
+    ("al.", "alemán"),
+    ("Am.", "América"),
+    ("Anat.", "Anatomía"),
+    ("ant.", "antiguo"),
+    ("Ant.", "antónimo"),
+    ("apl.", "aplicado"),
+    ("ár.", "árabe"),
+    ("Arq.", "Arquitectura"),
+    ("art.", "artículo"),
+    ("aum.", "aumentativo"),
+    ("aux.", "verbo auxiliar"),
+    ("Biol.", "Biología"),
+    ("Bot.", "Botánica"),
+    ("c.", "como"),
+    ("cat.", "catalán"),
+    ("coloq.", "coloquial"),
+    ("com.", "nombre común en cuanto al género"),
+    ("comp.", "comparativo"),
+    ("conj.", "conjunción"),
+    ("cult.", "culto"),
+    ("d.", "diminutivo"),
+    ("Der.", "Derecho"),
+    ("desp.", "despectivo"),
+    ("dim.", "diminutivo"),
+    ("Esp.", "España"),
+    ("etc.", "etcétera"),
+    ("expr.", "expresión"),
+    ("f.", "nombre femenino"),
+    ("fam.", "familiar"),
+    ("fest.", "festivo"),
+    ("fig.", "figurado"),
+    ("Fil.", "Filosofía"),
+    ("Fís.", "Física"),
+    ("fr.", "francés"),
+    ("gr.", "griego"),
+    ("Gram.", "Gramática"),
+    ("hebr.", "hebreo"),
+    ("impers.", "verbo impersonal"),
+    ("ingl.", "inglés"),
+    ("interj.", "interjección"),
+    ("intr.", "verbo intransitivo"),
+    ("irón.", "irónico"),
+    ("it.", "italiano"),
+    ("lat.", "latín"),
+    ("loc.", "locución"),
+    ("m.", "nombre masculino"),
+    ("Mar.", "Marina"),
+    ("Mat.", "Matemáticas"),
+    ("Med.", "Medicina"),
+    ("Mil.", "Milicia"),
+    ("Mús.", "Música"),
+    ("n.", "nombre"),
+    ("p.", "participio"),
+    ("p. us.", "poco usado"),
+    ("part.", "participio"),
+    ("pl.", "plural"),
+    ("poét.", "poético"),
+    ("pref.", "prefijo"),
+    ("prep.", "preposición"),
+    ("prnl.", "verbo pronominal"),
+    ("pron.", "pronombre"),
+    ("Quím.", "Química"),
+    ("rur.", "rural"),
+    ("s.", "sustantivo"),
+    ("Sin.", "sinónimo"),
+    ("sing.", "singular"),
+    ("suf.", "sufijo"),
+    ("sust.", "sustantivo"),
+    ("t.", "también"),
+    ("tr.", "verbo transitivo"),
+    ("U.", "usado"),
+    ("U. m.", "usado más"),
+    ("U. t.", "usado también"),
+    ("U. t. c. s.", "usado también como sustantivo"),
+    ("U. t. c. prnl.", "usado también como pronominal"),
+    ("U. t. c. intr.", "usado también como intransitivo"),
+    ("U. t. c. tr.", "usado también como transitivo"),
+    ("U. m. en pl.", "usado más en plural"),
+    ("U. t. en pl.", "usado también en plural"),
+    ("V.", "véase"),
+    ("vulg.", "vulgar"),
+];
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Abbreviation::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Abbreviation::Abbreviation)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Abbreviation::Expansion).string().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        let mut insert = Query::insert();
+        insert
+            .into_table(Abbreviation::Table)
+            .columns([Abbreviation::Abbreviation, Abbreviation::Expansion]);
+        for (abbreviation, expansion) in ABBREVIATIONS {
+            insert.values_panic([abbreviation.into(), expansion.into()]);
+        }
+
+        manager.exec_stmt(insert.to_owned()).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Abbreviation::Table).to_owned())
+            .await
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+#[allow(clippy::enum_variant_names)]
+enum Abbreviation {
+    Table,
+    Abbreviation,
+    Expansion,
+}
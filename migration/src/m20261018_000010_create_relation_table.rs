@@ -0,0 +1,101 @@
+use sea_orm_migration::prelude::*;
+use sea_query::extension::postgres::Type;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_type(
+                Type::create()
+                    .as_enum(RelationKind::Table)
+                    .values([
+                        RelationKind::Synonym,
+                        RelationKind::Antonym,
+                        RelationKind::Hypernym,
+                        RelationKind::Related,
+                    ])
+                    .to_owned(),
+            )
+            .await?;
+
+        // Relations between lemmas, `related` is the synonym, antonym, etc.
+        // of `lemma`. Imported from a CSV file.
+        manager
+            .create_table(
+                Table::create()
+                    .table(Relation::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(Relation::Lemma).string().not_null())
+                    .col(ColumnDef::new(Relation::Related).string().not_null())
+                    .col(
+                        ColumnDef::new(Relation::Kind)
+                            .enumeration(
+                                RelationKind::Table,
+                                [
+                                    RelationKind::Synonym,
+                                    RelationKind::Antonym,
+                                    RelationKind::Hypernym,
+                                    RelationKind::Related,
+                                ],
+                            )
+                            .not_null(),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(Relation::Lemma)
+                            .col(Relation::Related)
+                            .col(Relation::Kind),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Synonyms, antonyms and related words are looked up in both directions
+        manager
+            .create_index(
+                Index::create()
+                    .name("relation_related_idx")
+                    .table(Relation::Table)
+                    .col(Relation::Related)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Relation::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_type(
+                Type::drop()
+                    .if_exists()
+                    .name(RelationKind::Table)
+                    .restrict()
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+enum Relation {
+    Table,
+    Lemma,
+    Related,
+    Kind,
+}
+
+#[derive(Iden)]
+enum RelationKind {
+    Table, // Not really a Table but better than hardcoding the Iden impl by hand
+    Synonym,
+    Antonym,
+    Hypernym,
+    Related,
+}
@@ -0,0 +1,61 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Keys to find anagrams and rhymes, filled by `/reindexar`
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Dle::Table)
+                    .add_column(ColumnDef::new(Dle::AnagramKey).string().null())
+                    .add_column(ColumnDef::new(Dle::ConsonantRhyme).string().null())
+                    .add_column(ColumnDef::new(Dle::AssonantRhyme).string().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        for (name, column) in [
+            ("dle_anagram_key_idx", Dle::AnagramKey),
+            ("dle_consonant_rhyme_idx", Dle::ConsonantRhyme),
+            ("dle_assonant_rhyme_idx", Dle::AssonantRhyme),
+        ] {
+            manager
+                .create_index(
+                    Index::create()
+                        .name(name)
+                        .table(Dle::Table)
+                        .col(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Dle::Table)
+                    .drop_column(Dle::AnagramKey)
+                    .drop_column(Dle::ConsonantRhyme)
+                    .drop_column(Dle::AssonantRhyme)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+enum Dle {
+    Table,
+    AnagramKey,
+    ConsonantRhyme,
+    AssonantRhyme,
+}
@@ -0,0 +1,51 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // One row per subscriber and local date the word of the day was sent,
+        // so it's never sent twice the same day
+        manager
+            .create_table(
+                Table::create()
+                    .table(WotdDelivery::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(WotdDelivery::UserId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(WotdDelivery::Date).date().not_null())
+                    .col(
+                        ColumnDef::new(WotdDelivery::SentAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(WotdDelivery::UserId)
+                            .col(WotdDelivery::Date),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(WotdDelivery::Table).to_owned())
+            .await
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+enum WotdDelivery {
+    Table,
+    UserId,
+    Date,
+    SentAt,
+}
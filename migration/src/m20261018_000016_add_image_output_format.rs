@@ -0,0 +1,44 @@
+use sea_orm_migration::{
+    prelude::*,
+    sea_orm::{DbBackend, Statement},
+};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Definitions sent as an image like the word of the day
+        manager
+            .get_connection()
+            .execute(Statement::from_string(
+                DbBackend::Postgres,
+                r#"ALTER TYPE "output_format" ADD VALUE IF NOT EXISTS 'image'"#.to_string(),
+            ))
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Postgres can't drop a value of an enum, so the type is rebuilt
+        let db_conn = manager.get_connection();
+
+        for sql in [
+            r#"UPDATE "user" SET "output_format" = 'full' WHERE "output_format" = 'image'"#,
+            r#"ALTER TABLE "user" ALTER COLUMN "output_format" DROP DEFAULT"#,
+            r#"ALTER TYPE "output_format" RENAME TO "output_format_old""#,
+            r#"CREATE TYPE "output_format" AS ENUM ('full', 'compact')"#,
+            r#"ALTER TABLE "user" ALTER COLUMN "output_format" TYPE "output_format" USING "output_format"::text::"output_format""#,
+            r#"ALTER TABLE "user" ALTER COLUMN "output_format" SET DEFAULT 'full'"#,
+            r#"DROP TYPE "output_format_old""#,
+        ] {
+            db_conn
+                .execute(Statement::from_string(DbBackend::Postgres, sql.to_string()))
+                .await?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,64 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Channels and groups where the word of the day is posted, each at
+        // its own local time and with its own style
+        manager
+            .create_table(
+                Table::create()
+                    .table(WotdTarget::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(WotdTarget::ChatId)
+                            .big_integer()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(WotdTarget::DeliveryTime).time().not_null())
+                    .col(ColumnDef::new(WotdTarget::Timezone).string().not_null())
+                    .col(
+                        ColumnDef::new(WotdTarget::Theme)
+                            .string()
+                            .not_null()
+                            .default("random"),
+                    )
+                    .col(
+                        ColumnDef::new(WotdTarget::Language)
+                            .string()
+                            .not_null()
+                            .default("es"),
+                    )
+                    .col(
+                        ColumnDef::new(WotdTarget::Enabled)
+                            .boolean()
+                            .not_null()
+                            .default(true),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(WotdTarget::Table).to_owned())
+            .await
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+enum WotdTarget {
+    Table,
+    ChatId,
+    DeliveryTime,
+    Timezone,
+    Theme,
+    Language,
+    Enabled,
+}
@@ -0,0 +1,43 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // One row per chat and local date the word of the day was posted
+        // there, so it's never posted twice the same day
+        manager
+            .create_table(
+                Table::create()
+                    .table(WotdPost::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(WotdPost::ChatId).big_integer().not_null())
+                    .col(ColumnDef::new(WotdPost::Date).date().not_null())
+                    .col(
+                        ColumnDef::new(WotdPost::SentAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .primary_key(Index::create().col(WotdPost::ChatId).col(WotdPost::Date))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(WotdPost::Table).to_owned())
+            .await
+    }
+}
+
+/// Learn more at https://docs.rs/sea-query#iden
+#[derive(Iden)]
+enum WotdPost {
+    Table,
+    ChatId,
+    Date,
+    SentAt,
+}
@@ -0,0 +1,173 @@
+use teloxide::{prelude::*, utils::html::escape};
+
+use crate::{
+    database::{AbbreviationModel, DatabaseHandler, DleModel},
+    entry::strip_tags,
+    DLEBot,
+};
+
+/// Byte ranges where `abbreviation` appears in `text` as a whole word
+fn occurrences(text: &str, abbreviation: &str) -> Vec<(usize, usize)> {
+    text.match_indices(abbreviation)
+        .map(|(start, _)| (start, start + abbreviation.len()))
+        .filter(|&(start, end)| {
+            !text[..start]
+                .chars()
+                .next_back()
+                .is_some_and(char::is_alphabetic)
+                && !text[end..].chars().next().is_some_and(char::is_alphabetic)
+        })
+        .collect()
+}
+
+/// Abbreviations among `abbreviations` used in `definition`, in order of
+/// appearance. Those only used as part of a longer one (the `c.` of
+/// `U. t. c. s.`) are left out.
+pub fn abbreviations_in<'a>(
+    definition: &str,
+    abbreviations: &'a [AbbreviationModel],
+) -> Vec<&'a AbbreviationModel> {
+    let text = strip_tags(definition);
+
+    let mut by_length: Vec<&AbbreviationModel> = abbreviations.iter().collect();
+    by_length.sort_by_key(|abbreviation| std::cmp::Reverse(abbreviation.abbreviation.len()));
+
+    let mut covered: Vec<(usize, usize)> = vec![];
+    let mut found: Vec<(usize, &AbbreviationModel)> = vec![];
+
+    for abbreviation in by_length {
+        let free: Vec<(usize, usize)> = occurrences(&text, &abbreviation.abbreviation)
+            .into_iter()
+            .filter(|&(start, end)| {
+                !covered.iter().any(|&(covered_start, covered_end)| {
+                    covered_start <= start && end <= covered_end
+                })
+            })
+            .collect();
+
+        if let Some(&(first, _)) = free.first() {
+            found.push((first, abbreviation));
+            covered.extend(free);
+        }
+    }
+
+    found.sort_by_key(|&(position, _)| position);
+    found
+        .into_iter()
+        .map(|(_, abbreviation)| abbreviation)
+        .collect()
+}
+
+fn format_abbreviations(abbreviations: &[&AbbreviationModel]) -> String {
+    abbreviations
+        .iter()
+        .map(|abbreviation| {
+            format!(
+                "<b>{}</b>: {}",
+                abbreviation.abbreviation, abbreviation.expansion
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Send the abbreviations used in the definition of `word`
+pub async fn send_abbreviations_of(
+    db_handler: &DatabaseHandler,
+    bot: &DLEBot,
+    chat_id: ChatId,
+    word: &DleModel,
+) -> ResponseResult<()> {
+    let abbreviations = db_handler.get_abbreviations().await;
+    let used = abbreviations_in(&word.definition, &abbreviations);
+
+    let text = if used.is_empty() {
+        format!("La definición de «{}» no tiene abreviaturas.", word.lemma)
+    } else {
+        format!(
+            "Abreviaturas de «{}»:\n\n{}",
+            word.lemma,
+            format_abbreviations(&used)
+        )
+    };
+    bot.send_message(chat_id, text).await?;
+
+    Ok(())
+}
+
+/// Send the expansion of `query`, the final period is optional and if there
+/// is no abbreviation with the same case every one that differs only in
+/// case is shown
+pub async fn send_abbreviation(
+    db_handler: &DatabaseHandler,
+    bot: &DLEBot,
+    chat_id: ChatId,
+    query: &str,
+) -> ResponseResult<()> {
+    let query = query.trim();
+
+    if query.is_empty() {
+        bot.send_message(
+            chat_id,
+            "Escribe la abreviatura después del comando, por ejemplo: <code>/abreviatura prnl.</code>",
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let query = if query.ends_with('.') {
+        query.to_string()
+    } else {
+        format!("{}.", query)
+    };
+
+    let abbreviations = db_handler.get_abbreviations().await;
+    let mut matches: Vec<&AbbreviationModel> = abbreviations
+        .iter()
+        .filter(|abbreviation| abbreviation.abbreviation == query)
+        .collect();
+    if matches.is_empty() {
+        matches = abbreviations
+            .iter()
+            .filter(|abbreviation| abbreviation.abbreviation.to_lowercase() == query.to_lowercase())
+            .collect();
+    }
+
+    let text = if matches.is_empty() {
+        format!("No conozco la abreviatura «{}».", escape(&query))
+    } else {
+        format_abbreviations(&matches)
+    };
+    bot.send_message(chat_id, text).await?;
+
+    Ok(())
+}
+
+#[test]
+fn test_abbreviations_in() {
+    let abbreviations: Vec<AbbreviationModel> = [
+        ("adj.", "adjetivo"),
+        ("c.", "como"),
+        ("s.", "sustantivo"),
+        ("t.", "también"),
+        ("tr.", "verbo transitivo"),
+        ("U.", "usado"),
+        ("U. t. c. s.", "usado también como sustantivo"),
+    ]
+    .into_iter()
+    .map(|(abbreviation, expansion)| AbbreviationModel {
+        abbreviation: abbreviation.to_string(),
+        expansion: expansion.to_string(),
+    })
+    .collect();
+
+    let used: Vec<&str> = abbreviations_in(
+        "<b>1.</b> <i>adj.</i> Que tiene fuerza. <i>U. t. c. s.</i>\n<b>2.</b> <i>adj.</i> Robusto.",
+        &abbreviations,
+    )
+    .into_iter()
+    .map(|abbreviation| abbreviation.abbreviation.as_str())
+    .collect();
+
+    assert_eq!(used, ["adj.", "U. t. c. s."]);
+}
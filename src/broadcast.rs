@@ -19,22 +19,6 @@ async fn broadcast(message: String, users: Vec<i64>, bot: DLEBot) -> ResponseRes
     Ok(())
 }
 
-pub async fn _broadcast_word_of_the_day(
-    db_handler: DatabaseHandler,
-    bot: DLEBot,
-) -> ResponseResult<()> {
-    if let Ok(wotd) = db_handler.get_word_of_the_day().await {
-        broadcast(
-            format!("📖 Palabra del día\n\n {}", wotd.definition.clone().trim()),
-            db_handler._get_subscribed_and_in_bot_list().await,
-            bot,
-        )
-        .await?;
-    }
-
-    Ok(())
-}
-
 pub async fn broadcast_for_all(
     message: String,
     db_handler: DatabaseHandler,
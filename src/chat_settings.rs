@@ -0,0 +1,96 @@
+use teloxide::{
+    prelude::*,
+    types::{Chat, InlineKeyboardButton, InlineKeyboardMarkup, MaybeInaccessibleMessage, User},
+};
+
+use crate::{
+    database::{ChatModel, DatabaseHandler},
+    utils::CHAT_SETTINGS_CALLBACK_PREFIX,
+    DLEBot,
+};
+
+const COMMANDS_SETTING: &str = "commands";
+const WOTD_SETTING: &str = "wotd";
+
+/// Whether the bot answers in `chat` as in a group
+pub fn is_group(chat: &Chat) -> bool {
+    chat.is_group() || chat.is_supergroup()
+}
+
+const SETTINGS_TEXT: &str = "Ajustes del grupo, solo los administradores pueden cambiarlos.\n\nAquí siempre respondo a <code>/def palabra</code>, a los mensajes que empiezan mencionándome y a <code>/def</code> en respuesta a otro mensaje.";
+
+fn settings_keyboard(chat: &ChatModel) -> InlineKeyboardMarkup {
+    let mark = |enabled: bool| if enabled { "✅" } else { "❌" };
+
+    InlineKeyboardMarkup::new([
+        [InlineKeyboardButton::callback(
+            format!("{} Resto de comandos", mark(chat.commands_enabled)),
+            format!("{}{}", CHAT_SETTINGS_CALLBACK_PREFIX, COMMANDS_SETTING),
+        )],
+        [InlineKeyboardButton::callback(
+            format!("{} Palabra del día", mark(chat.wotd_enabled)),
+            format!("{}{}", CHAT_SETTINGS_CALLBACK_PREFIX, WOTD_SETTING),
+        )],
+    ])
+}
+
+/// Send the settings of the group of `msg` with buttons to change them
+pub async fn send_chat_settings(
+    db_handler: &DatabaseHandler,
+    bot: &DLEBot,
+    msg: &Message,
+) -> ResponseResult<()> {
+    if !is_group(&msg.chat) {
+        bot.send_message(
+            msg.chat.id,
+            "Este comando sirve para configurar el bot en un grupo, añádeme a uno y úsalo allí.",
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let chat = db_handler.get_chat(msg.chat.id.0).await;
+    bot.send_message(msg.chat.id, SETTINGS_TEXT)
+        .reply_markup(settings_keyboard(&chat))
+        .await?;
+
+    Ok(())
+}
+
+/// Switch the setting `setting` of the group of `message` if `user` is one
+/// of its administrators, and update the buttons
+pub async fn toggle_chat_setting(
+    db_handler: &DatabaseHandler,
+    bot: &DLEBot,
+    query_id: &str,
+    user: &User,
+    message: &MaybeInaccessibleMessage,
+    setting: &str,
+) -> ResponseResult<()> {
+    let chat_id = message.chat().id;
+
+    if !bot.get_chat_member(chat_id, user.id).await?.is_privileged() {
+        bot.answer_callback_query(query_id)
+            .text("Solo los administradores del grupo pueden cambiar los ajustes.")
+            .await?;
+        return Ok(());
+    }
+
+    let mut chat = db_handler.get_chat(chat_id.0).await;
+    match setting {
+        COMMANDS_SETTING => chat.commands_enabled = !chat.commands_enabled,
+        WOTD_SETTING => chat.wotd_enabled = !chat.wotd_enabled,
+        _ => {
+            bot.answer_callback_query(query_id).await?;
+            return Ok(());
+        }
+    }
+    db_handler.set_chat(chat.clone()).await;
+
+    bot.answer_callback_query(query_id).await?;
+    bot.edit_message_reply_markup(chat_id, message.id())
+        .reply_markup(settings_keyboard(&chat))
+        .await?;
+
+    Ok(())
+}
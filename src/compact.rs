@@ -0,0 +1,153 @@
+use teloxide::{
+    prelude::*,
+    types::{InlineKeyboardButton, InlineKeyboardMarkup, MaybeInaccessibleMessage, Me},
+};
+
+use crate::{
+    cross_reference::link_references,
+    database::{DatabaseHandler, DleModel},
+    entry::Entry,
+    handle_message::{definition_keyboard, full_definition, DefinitionContext},
+    utils::{
+        lemma_link, DISABLED_LINK_PREVIEW, FULL_DEFINITION_CALLBACK_PREFIX,
+        MAX_CALLBACK_DATA_LENGTH, MAX_MASSAGE_LENGTH, SENSE_CALLBACK_PREFIX,
+    },
+    DLEBot,
+};
+
+/// Compact view of `word`: the header, the etymology and only the sense at
+/// `index`, with buttons to move to the other senses or show the whole
+/// definition. Returns `None` when the entry has a single sense or the
+/// callback data of the buttons doesn't fit.
+pub fn compact_view(
+    word: &DleModel,
+    entry: &Entry,
+    index: usize,
+    context: &DefinitionContext,
+    me: &Me,
+) -> Option<(String, InlineKeyboardMarkup)> {
+    let senses = entry.all_senses();
+    if senses.len() <= 1 {
+        return None;
+    }
+    let (locution, sense) = senses.get(index)?;
+
+    let sense_data = |index: usize| {
+        format!(
+            "{}{}:{}:{}",
+            SENSE_CALLBACK_PREFIX, index, word.source, word.lemma
+        )
+    };
+    let full_data = format!(
+        "{}{}:{}",
+        FULL_DEFINITION_CALLBACK_PREFIX, word.source, word.lemma
+    );
+    if sense_data(senses.len() - 1).len() > MAX_CALLBACK_DATA_LENGTH
+        || full_data.len() > MAX_CALLBACK_DATA_LENGTH
+    {
+        return None;
+    }
+
+    let header = entry
+        .lemma
+        .replacen(&word.lemma, &lemma_link(me.username(), &word.lemma), 1);
+    let mut body = String::new();
+    if let Some(etymology) = &entry.etymology {
+        body += &format!("\n{}\n", etymology);
+    }
+    body += "\n";
+    if let Some(locution) = locution {
+        body += &format!("<b>{}</b>\n", locution);
+    }
+    body += &sense.to_html();
+    let text = format!(
+        "<b>{}</b>\n{}\n\n<i>Acepción {} de {}</i>",
+        header,
+        link_references(&body, &context.references, me.username()),
+        index + 1,
+        senses.len()
+    );
+    let text = context.with_footer(&text, word);
+
+    if text.len() > MAX_MASSAGE_LENGTH {
+        return None;
+    }
+
+    let mut navigation: Vec<InlineKeyboardButton> = vec![];
+    if index > 0 {
+        navigation.push(InlineKeyboardButton::callback("◀", sense_data(index - 1)));
+    }
+    if index + 1 < senses.len() {
+        navigation.push(InlineKeyboardButton::callback("▶", sense_data(index + 1)));
+    }
+
+    let mut keyboard = InlineKeyboardMarkup::new([
+        navigation,
+        vec![InlineKeyboardButton::callback("Ver todo", full_data)],
+    ]);
+    if let Some(actions) = definition_keyboard(word, context) {
+        keyboard.inline_keyboard.extend(actions.inline_keyboard);
+    }
+
+    Some((text, keyboard))
+}
+
+/// Edit the compact view in `message` to show the sense at `index`
+pub async fn show_sense(
+    db_handler: &DatabaseHandler,
+    bot: &DLEBot,
+    message: &MaybeInaccessibleMessage,
+    word: DleModel,
+    user_id: i64,
+    index: usize,
+    me: &Me,
+) -> ResponseResult<()> {
+    let context = DefinitionContext::load(db_handler, &word, user_id).await;
+
+    if let Some((text, keyboard)) = Entry::parse(&word.definition)
+        .and_then(|entry| compact_view(&word, &entry, index, &context, me))
+    {
+        bot.edit_message_text(message.chat().id, message.id(), text)
+            .link_preview_options(DISABLED_LINK_PREVIEW)
+            .reply_markup(keyboard)
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Replace the compact view in `message` with the full definition, the
+/// parts that don't fit in it are sent as new messages
+pub async fn show_full_definition(
+    db_handler: &DatabaseHandler,
+    bot: &DLEBot,
+    message: &MaybeInaccessibleMessage,
+    word: DleModel,
+    user_id: i64,
+    me: &Me,
+) -> ResponseResult<()> {
+    let context = DefinitionContext::load(db_handler, &word, user_id).await;
+    let (parts, keyboard) = full_definition(&word, &context, me);
+    let last_index = parts.len() - 1;
+
+    for (index, part) in parts.into_iter().enumerate() {
+        let keyboard = match &keyboard {
+            Some(keyboard) if index == last_index => keyboard.clone(),
+            _ => InlineKeyboardMarkup::default(),
+        };
+
+        if index == 0 {
+            bot.edit_message_text(message.chat().id, message.id(), part)
+                .link_preview_options(DISABLED_LINK_PREVIEW)
+                .reply_markup(keyboard)
+                .await?;
+        } else {
+            bot.send_message(message.chat().id, part)
+                .link_preview_options(DISABLED_LINK_PREVIEW)
+                .reply_markup(keyboard)
+                .await?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,334 @@
+use serde::Deserialize;
+use teloxide::{prelude::*, utils::html::escape};
+
+use crate::{
+    database::{DatabaseHandler, DleModel},
+    utils::{smart_split, MAX_MASSAGE_LENGTH},
+    DLEBot,
+};
+
+/// Typed model of the `dle.conjugation` column.
+///
+/// The JSON mirrors the conjugation tables of the DLE, every key is optional
+/// so incomplete paradigms (defective verbs, for instance) still deserialize:
+///
+/// ```json
+/// {
+///   "formas_no_personales": { "infinitivo": "cantar", "gerundio": "cantando", ... },
+///   "indicativo": { "presente": { "yo": "canto", "tu": "cantas / cantás", ... }, ... },
+///   "subjuntivo": { "presente": { "yo": "cante", ... }, ... },
+///   "imperativo": { "tu": "canta / cantá", "usted": "cante", ... }
+/// }
+/// ```
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Conjugation {
+    pub formas_no_personales: NonPersonalForms,
+    pub indicativo: Indicative,
+    pub subjuntivo: Subjunctive,
+    pub imperativo: Imperative,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct NonPersonalForms {
+    pub infinitivo: Option<String>,
+    pub gerundio: Option<String>,
+    pub participio: Option<String>,
+    pub infinitivo_compuesto: Option<String>,
+    pub gerundio_compuesto: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Indicative {
+    pub presente: Option<Persons>,
+    pub preterito_imperfecto: Option<Persons>,
+    pub preterito_perfecto_simple: Option<Persons>,
+    pub futuro_simple: Option<Persons>,
+    pub condicional_simple: Option<Persons>,
+    pub preterito_perfecto_compuesto: Option<Persons>,
+    pub preterito_pluscuamperfecto: Option<Persons>,
+    pub preterito_anterior: Option<Persons>,
+    pub futuro_compuesto: Option<Persons>,
+    pub condicional_compuesto: Option<Persons>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Subjunctive {
+    pub presente: Option<Persons>,
+    pub preterito_imperfecto: Option<Persons>,
+    pub futuro_simple: Option<Persons>,
+    pub preterito_perfecto_compuesto: Option<Persons>,
+    pub preterito_pluscuamperfecto: Option<Persons>,
+    pub futuro_compuesto: Option<Persons>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Imperative {
+    pub tu: Option<String>,
+    pub usted: Option<String>,
+    pub vosotros: Option<String>,
+    pub ustedes: Option<String>,
+}
+
+/// Forms of one tense, one field per row of the DLE table
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Persons {
+    pub yo: Option<String>,
+    pub tu: Option<String>,
+    pub usted: Option<String>,
+    pub el: Option<String>,
+    pub nosotros: Option<String>,
+    pub vosotros: Option<String>,
+    pub ustedes: Option<String>,
+    pub ellos: Option<String>,
+}
+
+impl Persons {
+    fn rows(&self) -> [(&'static str, &Option<String>); 8] {
+        [
+            ("yo", &self.yo),
+            ("tú / vos", &self.tu),
+            ("usted", &self.usted),
+            ("él, ella", &self.el),
+            ("nosotros, nosotras", &self.nosotros),
+            ("vosotros, vosotras", &self.vosotros),
+            ("ustedes", &self.ustedes),
+            ("ellos, ellas", &self.ellos),
+        ]
+    }
+}
+
+impl Indicative {
+    fn tenses(&self) -> [(&'static str, &Option<Persons>); 10] {
+        [
+            ("Presente", &self.presente),
+            (
+                "Pretérito imperfecto / Copretérito",
+                &self.preterito_imperfecto,
+            ),
+            (
+                "Pretérito perfecto simple / Pretérito",
+                &self.preterito_perfecto_simple,
+            ),
+            ("Futuro simple / Futuro", &self.futuro_simple),
+            (
+                "Condicional simple / Pospretérito",
+                &self.condicional_simple,
+            ),
+            (
+                "Pretérito perfecto compuesto / Antepresente",
+                &self.preterito_perfecto_compuesto,
+            ),
+            (
+                "Pretérito pluscuamperfecto / Antecopretérito",
+                &self.preterito_pluscuamperfecto,
+            ),
+            (
+                "Pretérito anterior / Antepretérito",
+                &self.preterito_anterior,
+            ),
+            ("Futuro compuesto / Antefuturo", &self.futuro_compuesto),
+            (
+                "Condicional compuesto / Antepospretérito",
+                &self.condicional_compuesto,
+            ),
+        ]
+    }
+}
+
+impl Subjunctive {
+    fn tenses(&self) -> [(&'static str, &Option<Persons>); 6] {
+        [
+            ("Presente", &self.presente),
+            (
+                "Pretérito imperfecto / Pretérito",
+                &self.preterito_imperfecto,
+            ),
+            ("Futuro simple / Futuro", &self.futuro_simple),
+            (
+                "Pretérito perfecto compuesto / Antepresente",
+                &self.preterito_perfecto_compuesto,
+            ),
+            (
+                "Pretérito pluscuamperfecto / Antepretérito",
+                &self.preterito_pluscuamperfecto,
+            ),
+            ("Futuro compuesto / Antefuturo", &self.futuro_compuesto),
+        ]
+    }
+}
+
+fn push_row(text: &mut String, label: &str, form: &Option<String>) {
+    if let Some(form) = form {
+        *text += &format!("<i>{}</i>: {}\n", label, form);
+    }
+}
+
+impl Conjugation {
+    /// Parse the content of the `conjugation` column
+    pub fn from_json(json: &serde_json::Value) -> Option<Self> {
+        serde_json::from_value(json.clone())
+            .map_err(|error| log::warn!("Malformed conjugation: {:?}", error))
+            .ok()
+    }
+
+    /// Conjugation of `word`, if it is a verb
+    pub fn from_word(word: &DleModel) -> Option<Self> {
+        word.conjugation.as_ref().and_then(Self::from_json)
+    }
+
+    /// Every simple (one word) form of the paradigm, lowercase.
+    /// Cells with alternatives like `cantas / cantás` or `cantara o cantase`
+    /// yield one form per alternative; compound tenses are skipped.
+    pub fn forms(&self) -> Vec<String> {
+        let forms = &self.formas_no_personales;
+        let imperative = &self.imperativo;
+        let mut cells: Vec<&Option<String>> = vec![
+            &forms.infinitivo,
+            &forms.gerundio,
+            &forms.participio,
+            &imperative.tu,
+            &imperative.usted,
+            &imperative.vosotros,
+            &imperative.ustedes,
+        ];
+
+        for (_, persons) in self
+            .indicativo
+            .tenses()
+            .iter()
+            .chain(self.subjuntivo.tenses().iter())
+        {
+            if let Some(persons) = persons {
+                cells.extend(persons.rows().map(|(_, form)| form));
+            }
+        }
+
+        let mut result: Vec<String> = cells
+            .into_iter()
+            .flatten()
+            .flat_map(|cell| cell.split(['/', ',']))
+            .flat_map(|alternative| alternative.split(" o "))
+            .map(|form| form.trim().to_lowercase())
+            .filter(|form| !form.is_empty() && !form.contains(char::is_whitespace))
+            .collect();
+
+        result.sort();
+        result.dedup();
+
+        result
+    }
+
+    /// Render the full paradigm as Telegram HTML.
+    /// Every line is self-contained (no tag spans more than one line) so the
+    /// result can be cut by `smart_split` without breaking the markup.
+    pub fn to_html(&self, lemma: &str) -> String {
+        let mut text = format!("<b>{}</b> — Conjugación\n", lemma);
+
+        text += "\n<b>FORMAS NO PERSONALES</b>\n";
+        let forms = &self.formas_no_personales;
+        push_row(&mut text, "Infinitivo", &forms.infinitivo);
+        push_row(&mut text, "Gerundio", &forms.gerundio);
+        push_row(&mut text, "Participio", &forms.participio);
+        push_row(
+            &mut text,
+            "Infinitivo compuesto",
+            &forms.infinitivo_compuesto,
+        );
+        push_row(&mut text, "Gerundio compuesto", &forms.gerundio_compuesto);
+
+        for (mood, tenses) in [
+            ("INDICATIVO", &self.indicativo.tenses()[..]),
+            ("SUBJUNTIVO", &self.subjuntivo.tenses()[..]),
+        ] {
+            if tenses.iter().all(|(_, persons)| persons.is_none()) {
+                continue;
+            }
+
+            text += &format!("\n<b>{}</b>\n", mood);
+            for (name, persons) in tenses {
+                if let Some(persons) = persons {
+                    text += &format!("\n<u>{}</u>\n", name);
+                    for (label, form) in persons.rows() {
+                        push_row(&mut text, label, form);
+                    }
+                }
+            }
+        }
+
+        text += "\n<b>IMPERATIVO</b>\n";
+        let imperative = &self.imperativo;
+        push_row(&mut text, "tú / vos", &imperative.tu);
+        push_row(&mut text, "usted", &imperative.usted);
+        push_row(&mut text, "vosotros, vosotras", &imperative.vosotros);
+        push_row(&mut text, "ustedes", &imperative.ustedes);
+
+        text
+    }
+}
+
+/// Send the conjugation of `verb` to `chat_id`
+pub async fn send_conjugation(
+    db_handler: DatabaseHandler,
+    bot: DLEBot,
+    chat_id: ChatId,
+    verb: &str,
+) -> ResponseResult<()> {
+    let verb = verb.trim();
+
+    if verb.is_empty() {
+        bot.send_message(
+            chat_id,
+            "Escribe el verbo después del comando, por ejemplo: <code>/conjugar cantar</code>",
+        )
+        .await?;
+        return Ok(());
+    }
+
+    match db_handler.get_exact(verb).await {
+        Some(word) => match Conjugation::from_word(&word) {
+            Some(conjugation) => {
+                for part in smart_split(&conjugation.to_html(&word.lemma), MAX_MASSAGE_LENGTH) {
+                    bot.send_message(chat_id, part).await?;
+                }
+            }
+            None => {
+                bot.send_message(chat_id, format!("«{}» no es un verbo.", word.lemma))
+                    .await?;
+            }
+        },
+        None => {
+            bot.send_message(chat_id, format!("No encontré el verbo «{}».", escape(verb)))
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_conjugation_to_html() {
+    let json = serde_json::json!({
+        "formas_no_personales": { "infinitivo": "cantar", "gerundio": "cantando" },
+        "indicativo": {
+            "presente": { "yo": "canto", "tu": "cantas / cantás", "nosotros": "cantamos" }
+        },
+        "imperativo": { "tu": "canta / cantá" }
+    });
+    let conjugation = Conjugation::from_json(&json).unwrap();
+    let html = conjugation.to_html("cantar");
+
+    assert!(html.contains("<i>Gerundio</i>: cantando\n"));
+    assert!(html.contains("<u>Presente</u>\n<i>yo</i>: canto\n<i>tú / vos</i>: cantas / cantás\n"));
+    assert!(html.contains("<i>tú / vos</i>: canta / cantá\n"));
+    assert!(!html.contains("Futuro"));
+    assert_eq!(
+        conjugation.forms(),
+        ["canta", "cantamos", "cantando", "cantar", "cantas", "canto", "cantá", "cantás"]
+    );
+}
@@ -0,0 +1,119 @@
+use std::{ops::Range, sync::LazyLock};
+
+use regex::Regex;
+
+use crate::{database::DatabaseHandler, utils::lemma_link_with_text};
+
+/// References to a single lemma, captured in the first group
+static SINGLE_REFERENCES: LazyLock<[Regex; 3]> = LazyLock::new(|| {
+    [
+        Regex::new(r"\bV\.\s+(?:<i>)?(\p{L}+)").unwrap(),
+        Regex::new(r"(?i)\bsin[oó]nimo de\s+(?:<i>)?(\p{L}+)").unwrap(),
+        Regex::new(r"(\p{L}+)(?:</i>)?\s*\(‖").unwrap(),
+    ]
+});
+/// Lists of synonyms or antonyms separated by commas
+static REFERENCE_LIST: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\b(?:Sin|Ant)\.:([^.\n]+)").unwrap());
+static REFERENCE_LIST_ITEM: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s*(?:<i>)?(\p{L}+(?: \p{L}+)*)(?:</i>)?\s*$").unwrap());
+
+/// Byte ranges of `definition` that refer to another lemma, with the lemma
+/// in lowercase.
+/// Recognizes the usual ways the DLE points to other entries:
+///
+/// - `V. casa` or `V. <i>casa</i>`
+/// - `Sin.: hogar, domicilio.` and `Ant.: ...`
+/// - `sinónimo de casa`
+/// - `edificio (‖ construcción)`, the word before the double bar
+fn reference_ranges(definition: &str) -> Vec<(Range<usize>, String)> {
+    let mut ranges: Vec<(Range<usize>, String)> = vec![];
+
+    for pattern in SINGLE_REFERENCES.iter() {
+        for captures in pattern.captures_iter(definition) {
+            let lemma = captures.get(1).unwrap();
+            ranges.push((lemma.range(), lemma.as_str().to_lowercase()));
+        }
+    }
+
+    for captures in REFERENCE_LIST.captures_iter(definition) {
+        let items = captures.get(1).unwrap();
+        let mut start = items.start();
+        for text in items.as_str().split(',') {
+            if let Some(lemma) = REFERENCE_LIST_ITEM
+                .captures(text)
+                .and_then(|captures| captures.get(1))
+            {
+                ranges.push((
+                    start + lemma.start()..start + lemma.end(),
+                    lemma.as_str().to_lowercase(),
+                ));
+            }
+            start += text.len() + 1;
+        }
+    }
+
+    ranges.sort_by_key(|(range, _)| range.start);
+    ranges.dedup_by(|b, a| b.0.start < a.0.end);
+
+    ranges
+}
+
+/// Lemmas `definition` refers to, to be checked against the dictionary
+pub fn references(definition: &str) -> Vec<String> {
+    let mut lemmas: Vec<String> = reference_ranges(definition)
+        .into_iter()
+        .map(|(_, lemma)| lemma)
+        .collect();
+    lemmas.sort();
+    lemmas.dedup();
+
+    lemmas
+}
+
+/// Lemmas `definition` refers to that have an entry in the dictionary
+pub async fn existing_references(db_handler: &DatabaseHandler, definition: &str) -> Vec<String> {
+    db_handler
+        .filter_existing_lemmas(&references(definition))
+        .await
+}
+
+/// Turn every reference of `definition` to one of `lemmas` into a deep link
+/// to its definition
+pub fn link_references(definition: &str, lemmas: &[String], bot_username: &str) -> String {
+    let mut result = String::new();
+    let mut last = 0;
+
+    for (range, lemma) in reference_ranges(definition) {
+        if lemmas.contains(&lemma) {
+            result += &definition[last..range.start];
+            result += &lemma_link_with_text(bot_username, &lemma, &definition[range.clone()]);
+            last = range.end;
+        }
+    }
+    result += &definition[last..];
+
+    result
+}
+
+#[test]
+fn test_references() {
+    let definition = "<b>hogar</b>\n\n<b>1.</b> <i>m.</i> Sitio donde se hace la lumbre. V. <i>chimenea</i>.\n<b>2.</b> <i>m.</i> Casa o domicilio.\nSin.: casa, domicilio, <i>morada</i>.\n<b>3.</b> <i>m.</i> Familia (‖ grupo de personas). Sinónimo de familia.";
+
+    assert_eq!(
+        references(definition),
+        ["casa", "chimenea", "domicilio", "familia", "morada"]
+    );
+    assert_eq!(
+        link_references(
+            "V. <i>chimenea</i>. Sin.: casa, techo.",
+            &["chimenea".to_string(), "casa".to_string()],
+            "dlebot"
+        ),
+        format!(
+            "V. <i>{}</i>. Sin.: {}, techo.",
+            lemma_link_with_text("dlebot", "chimenea", "chimenea"),
+            lemma_link_with_text("dlebot", "casa", "casa")
+        )
+    );
+}
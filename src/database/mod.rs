@@ -1,21 +1,112 @@
 mod schema;
 
 use sea_orm::{
-    entity::prelude::DateTimeWithTimeZone, sea_query::Expr, ActiveModelTrait, ColumnTrait,
-    ConnectOptions, Database, DatabaseConnection, DbBackend, DbErr, EntityTrait, QueryFilter,
-    QueryOrder, QuerySelect, Set, Statement, Value,
+    entity::prelude::DateTimeWithTimeZone,
+    sea_query::{Expr, OnConflict},
+    ActiveModelTrait, ColumnTrait, ConnectOptions, ConnectionTrait, Database, DatabaseConnection,
+    DbBackend, DbErr, EntityTrait, FromQueryResult, PaginatorTrait, QueryFilter, QueryOrder,
+    QuerySelect, Set, Statement, TransactionTrait, Value,
 };
 use std::env;
 
-use chrono::{offset::Local, NaiveDate};
+use chrono::{offset::Local, DateTime, NaiveDate, NaiveTime, Utc};
 use schema::{
-    event,
-    prelude::{Dle, User, WordOfTheDay},
+    chat, dictionary, dle, event, inflection, locution, phonetic,
+    prelude::{
+        Abbreviation, Chat, Dictionary, Dle, Inflection, Locution, Phonetic, Relation,
+        ScheduledJob, User, WordOfTheDay, WotdDelivery, WotdPost, WotdTarget,
+    },
+    relation,
     sea_orm_active_enums::EventType,
-    user, word_of_the_day,
+    user, word_of_the_day, wotd_delivery, wotd_post, wotd_target,
+};
+
+use crate::{
+    entry::Entry,
+    fuzzy::score,
+    inflection::inflected_forms,
+    pattern::Pattern,
+    phonetic::phonetic_key,
+    rhyme::{anagram_key, assonant_rhyme, consonant_rhyme},
 };
 
 pub type DleModel = schema::dle::Model;
+pub type DictionaryModel = schema::dictionary::Model;
+pub type LocutionModel = schema::locution::Model;
+pub type AbbreviationModel = schema::abbreviation::Model;
+pub type ChatModel = schema::chat::Model;
+pub type WotdTargetModel = schema::wotd_target::Model;
+pub use schema::sea_orm_active_enums::{OutputFormat, RelationKind};
+
+/// Code of the dictionary used when no other is specified
+pub const DEFAULT_DICTIONARY: &str = "dle";
+
+/// Time zone of the users that never chose one
+pub const DEFAULT_TIMEZONE: &str = "Europe/Madrid";
+
+/// Local time at which users that never chose one get the word of the day
+pub fn default_delivery_time() -> NaiveTime {
+    NaiveTime::from_hms_opt(9, 0, 0).unwrap_or_default()
+}
+
+/// Entries picked automatically as word of the day have definitions of at
+/// least this many characters, and at most the maximum so they fit in the
+/// caption of the image
+const WOTD_MIN_DEFINITION_LENGTH: i32 = 200;
+const WOTD_MAX_DEFINITION_LENGTH: i32 = 900;
+/// Shorter lemmas are never picked automatically as word of the day
+const WOTD_MIN_LEMMA_LENGTH: i32 = 4;
+/// Days before a word of the day can be picked again
+const WOTD_REPEAT_DAYS: i64 = 730;
+/// Entries with any of these usage marks are never picked automatically
+const WOTD_EXCLUDED_MARKS: [&str; 5] = ["malson.", "vulg.", "despect.", "peyor.", "insult."];
+/// Key of the advisory lock taken while picking a word of the day
+const WOTD_PICK_LOCK: i64 = 0x57_4f_54_44;
+
+/// Minimum trigram similarity of the candidates of the fuzzy search
+const FUZZY_SIMILARITY_THRESHOLD: f32 = 0.2;
+/// Number of candidates of the fuzzy search that get ranked
+const FUZZY_CANDIDATES: u64 = 50;
+/// Longest word accepted by the fuzzy search
+const MAX_FUZZY_WORD_LENGTH: usize = 100;
+
+/// Number of dictionary rows processed at once when rebuilding indexes
+const INDEX_PAGE_SIZE: u64 = 1000;
+/// Number of rows per `INSERT` when rebuilding indexes
+const INDEX_INSERT_BATCH_SIZE: usize = 10000;
+
+#[derive(FromQueryResult)]
+struct LemmaResult {
+    lemma: String,
+}
+
+#[derive(FromQueryResult)]
+struct CountResult {
+    count: i64,
+}
+
+/// Expression of the lemma compared with `pattern`, the patterns that ignore
+/// written accents use the indexed `unaccent_lemma`
+fn pattern_column(pattern: &Pattern) -> &'static str {
+    if pattern.accent_insensitive {
+        r#"unaccent_lemma("dle"."lemma")"#
+    } else {
+        r#"LOWER("dle"."lemma")"#
+    }
+}
+
+#[derive(FromQueryResult)]
+pub struct RelatedLemma {
+    pub related: String,
+    pub kind: RelationKind,
+}
+
+#[derive(FromQueryResult)]
+struct FuzzyCandidate {
+    lemma: String,
+    similarity: f32,
+    distance: i32,
+}
 
 #[derive(Clone)]
 pub struct DatabaseHandler {
@@ -39,14 +130,26 @@ impl DatabaseHandler {
         Self::new(env::var("DATABASE_URL").unwrap()).await
     }
 
-    /// Get list of the 10 first rows whose "lemma" starts with `query`.
+    /// Get list of `limit` rows whose "lemma" starts with `query`, skipping the
+    /// first `offset`, only from the dictionary `source` if given.
     /// This is case insensitive.
-    pub async fn get_list_like(&self, query: &str) -> Vec<DleModel> {
+    pub async fn get_list_like(
+        &self,
+        query: &str,
+        offset: u64,
+        limit: u64,
+        source: Option<&str>,
+    ) -> Vec<DleModel> {
         Dle::find()
         .from_raw_sql(Statement::from_sql_and_values(
             DbBackend::Postgres,
-            r#"SELECT * FROM "dle" WHERE "dle"."lemma" ILIKE $1 ORDER BY "dle"."lemma" ASC LIMIT 10"#,
-                [(format!("{}%", query)).into()],
+            r#"SELECT "dle".* FROM "dle" JOIN "dictionary" ON "dictionary"."code" = "dle"."source" WHERE "dle"."lemma" ILIKE $1 AND ($2::text IS NULL OR "dle"."source" = $2) ORDER BY "dle"."lemma" ASC, "dictionary"."priority" ASC OFFSET $3 LIMIT $4"#,
+                [
+                    (format!("{}%", query)).into(),
+                    source.map(str::to_string).into(),
+                    offset.into(),
+                    limit.into(),
+                ],
             ))
             .all(&self.db)
             .await
@@ -56,14 +159,29 @@ impl DatabaseHandler {
             })
     }
 
-    /// Get list of the 5 first lemmas that match `word` within distance of two.
-    /// This is case insensitive.
-    pub async fn get_fuzzy_list(&self, word: &str) -> Vec<String> {
+    /// Get rows whose lemma matches `pattern`, one per lemma from the
+    /// dictionary with the highest priority
+    pub async fn get_pattern_matches(
+        &self,
+        pattern: &Pattern,
+        offset: u64,
+        limit: u64,
+    ) -> Vec<DleModel> {
         Dle::find()
             .from_raw_sql(Statement::from_sql_and_values(
                 DbBackend::Postgres,
-                r#"SELECT * FROM "dle" WHERE levenshtein(LOWER("lemma"), LOWER($1)) < 2 ORDER BY "lemma" LIMIT 5"#,
-                [word.into()],
+                format!(
+                    r#"SELECT DISTINCT ON ("dle"."lemma") "dle".* FROM "dle" JOIN "dictionary" ON "dictionary"."code" = "dle"."source" WHERE {} LIKE $1 AND char_length("dle"."lemma") BETWEEN $2 AND $3 ORDER BY "dle"."lemma" ASC, "dictionary"."priority" ASC OFFSET $4 LIMIT $5"#,
+                    pattern_column(pattern)
+                )
+                .as_str(),
+                [
+                    pattern.like.clone().into(),
+                    pattern.min_length.unwrap_or(0).into(),
+                    pattern.max_length.unwrap_or(i32::MAX as u32).into(),
+                    offset.into(),
+                    limit.into(),
+                ],
             ))
             .all(&self.db)
             .await
@@ -71,17 +189,101 @@ impl DatabaseHandler {
                 log::error!("Error accessing the database: {:?}", x);
                 vec![]
             })
-            .iter()
-            .map(|row| row.lemma.clone())
+    }
+
+    /// Count the lemmas that match `pattern`
+    pub async fn count_pattern_matches(&self, pattern: &Pattern) -> u64 {
+        CountResult::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            format!(
+                r#"SELECT COUNT(DISTINCT "dle"."lemma") AS "count" FROM "dle" WHERE {} LIKE $1 AND char_length("dle"."lemma") BETWEEN $2 AND $3"#,
+                pattern_column(pattern)
+            )
+            .as_str(),
+            [
+                pattern.like.clone().into(),
+                pattern.min_length.unwrap_or(0).into(),
+                pattern.max_length.unwrap_or(i32::MAX as u32).into(),
+            ],
+        ))
+        .one(&self.db)
+        .await
+        .map(|result| result.map_or(0, |result| result.count as u64))
+        .unwrap_or_else(|x| {
+            log::error!("Error accessing the database: {:?}", x);
+            0
+        })
+    }
+
+    /// Get list of the `limit` lemmas most similar to `word`. Candidates are the
+    /// lemmas with a trigram similarity above `FUZZY_SIMILARITY_THRESHOLD`,
+    /// ranked by `fuzzy::score`. This is case and accent insensitive.
+    pub async fn get_fuzzy_list(&self, word: &str, limit: usize) -> Vec<String> {
+        // levenshtein() does not accept longer strings
+        if word.len() > MAX_FUZZY_WORD_LENGTH {
+            return vec![];
+        }
+
+        let candidates = async {
+            let txn = self.db.begin().await?;
+
+            txn.execute(Statement::from_string(
+                DbBackend::Postgres,
+                format!(
+                    "SET LOCAL pg_trgm.similarity_threshold = {}",
+                    FUZZY_SIMILARITY_THRESHOLD
+                ),
+            ))
+            .await?;
+
+            let candidates = FuzzyCandidate::find_by_statement(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                r#"SELECT DISTINCT "lemma", similarity(unaccent_lemma("lemma"), unaccent_lemma($1)) AS "similarity", levenshtein(LOWER("lemma"), LOWER($1)) AS "distance" FROM "dle" WHERE unaccent_lemma("lemma") % unaccent_lemma($1) ORDER BY "similarity" DESC LIMIT $2"#,
+                [word.into(), FUZZY_CANDIDATES.into()],
+            ))
+            .all(&txn)
+            .await?;
+
+            txn.commit().await?;
+
+            Ok::<_, DbErr>(candidates)
+        }
+        .await
+        .unwrap_or_else(|x| {
+            log::error!("Error accessing the database: {:?}", x);
+            vec![]
+        });
+
+        let mut ranked: Vec<(f32, String)> = candidates
+            .into_iter()
+            .map(|candidate| {
+                (
+                    score(
+                        word,
+                        &candidate.lemma,
+                        candidate.similarity,
+                        candidate.distance,
+                    ),
+                    candidate.lemma,
+                )
+            })
+            .collect();
+        ranked.sort_by(|a, b| b.0.total_cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+
+        ranked
+            .into_iter()
+            .take(limit)
+            .map(|(_, lemma)| lemma)
             .collect()
     }
 
-    /// Get row with "lemma" == `lemma`. Case insensitive.
+    /// Get row with "lemma" == `lemma` from the dictionary with the highest
+    /// priority that defines it. Case insensitive.
     pub async fn get_exact(&self, lemma: &str) -> Option<DleModel> {
         Dle::find()
             .from_raw_sql(Statement::from_sql_and_values(
                 DbBackend::Postgres,
-                r#"SELECT * FROM "dle" WHERE "dle"."lemma" ILIKE $1 LIMIT 1"#,
+                r#"SELECT "dle".* FROM "dle" JOIN "dictionary" ON "dictionary"."code" = "dle"."source" WHERE lower("dle"."lemma") = lower($1) ORDER BY "dictionary"."priority" ASC LIMIT 1"#,
                 [lemma.into()],
             ))
             .one(&self.db)
@@ -92,6 +294,351 @@ impl DatabaseHandler {
             })
     }
 
+    /// Get row with "lemma" == `lemma` from the dictionary `source`. Case insensitive.
+    pub async fn get_exact_in(&self, lemma: &str, source: &str) -> Option<DleModel> {
+        Dle::find()
+            .from_raw_sql(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                r#"SELECT * FROM "dle" WHERE lower("dle"."lemma") = lower($1) AND "dle"."source" = $2 LIMIT 1"#,
+                [lemma.into(), source.into()],
+            ))
+            .one(&self.db)
+            .await
+            .unwrap_or_else(|x| {
+                log::error!("Error accessing the database: {:?}", x);
+                None
+            })
+    }
+
+    /// Get the `limit` rows whose "definition" best matches `description`,
+    /// ranked by `ts_rank`. Any of the words of `description` may match.
+    pub async fn search_definitions(&self, description: &str, limit: u64) -> Vec<DleModel> {
+        // Only the words, so nothing in `description` is read as an operator
+        // of `websearch_to_tsquery`, joined by its OR operator
+        let query = description
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .collect::<Vec<&str>>()
+            .join(" or ");
+
+        Dle::find()
+            .from_raw_sql(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                r#"WITH "q" AS (SELECT websearch_to_tsquery('spanish', $1) AS "query") SELECT "dle".* FROM "dle", "q" WHERE to_tsvector('spanish', "dle"."definition") @@ "q"."query" ORDER BY ts_rank(to_tsvector('spanish', "dle"."definition"), "q"."query") DESC, "dle"."lemma" ASC LIMIT $2"#,
+                [query.into(), limit.into()],
+            ))
+            .all(&self.db)
+            .await
+            .unwrap_or_else(|x| {
+                log::error!("Error accessing the database: {:?}", x);
+                vec![]
+            })
+    }
+
+    /// Get rows whose "lemma" equals `word` ignoring case and written accents,
+    /// one per lemma from the dictionary with the highest priority.
+    /// Rows that match the accents as written come first.
+    pub async fn get_accent_insensitive(&self, word: &str) -> Vec<DleModel> {
+        Dle::find()
+            .from_raw_sql(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                r#"SELECT * FROM (SELECT DISTINCT ON ("dle"."lemma") "dle".* FROM "dle" JOIN "dictionary" ON "dictionary"."code" = "dle"."source" WHERE unaccent_lemma("dle"."lemma") = unaccent_lemma($1) ORDER BY "dle"."lemma", "dictionary"."priority") AS "dle" ORDER BY (LOWER("dle"."lemma") = LOWER($1)) DESC, "dle"."lemma" ASC LIMIT 10"#,
+                [word.into()],
+            ))
+            .all(&self.db)
+            .await
+            .unwrap_or_else(|x| {
+                log::error!("Error accessing the database: {:?}", x);
+                vec![]
+            })
+    }
+
+    /// Get rows that have `form` among their inflected forms, one per lemma from
+    /// the dictionary with the highest priority. Case insensitive, accents are
+    /// ignored only if no form matches them as written.
+    pub async fn get_lemmas_of_form(&self, form: &str) -> Vec<DleModel> {
+        Dle::find()
+            .from_raw_sql(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                r#"SELECT DISTINCT ON ("dle"."lemma") "dle".* FROM "dle" JOIN "dictionary" ON "dictionary"."code" = "dle"."source" WHERE "dle"."lemma" IN (SELECT "lemma" FROM "inflection" WHERE unaccent_lemma("form") = unaccent_lemma($1) AND ("form" = $1 OR NOT EXISTS (SELECT 1 FROM "inflection" WHERE "form" = $1))) ORDER BY "dle"."lemma" ASC, "dictionary"."priority" ASC"#,
+                [form.to_lowercase().into()],
+            ))
+            .all(&self.db)
+            .await
+            .unwrap_or_else(|x| {
+                log::error!("Error accessing the database: {:?}", x);
+                vec![]
+            })
+    }
+
+    /// Get rows whose lemma sounds like `word` according to `phonetic_key`,
+    /// one per lemma from the dictionary with the highest priority.
+    pub async fn get_homophones(&self, word: &str) -> Vec<DleModel> {
+        let key = phonetic_key(word);
+        if key.is_empty() {
+            return vec![];
+        }
+
+        Dle::find()
+            .from_raw_sql(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                r#"SELECT DISTINCT ON ("dle"."lemma") "dle".* FROM "dle" JOIN "dictionary" ON "dictionary"."code" = "dle"."source" WHERE "dle"."lemma" IN (SELECT "lemma" FROM "phonetic" WHERE "key" = $1) ORDER BY "dle"."lemma" ASC, "dictionary"."priority" ASC LIMIT 10"#,
+                [key.into()],
+            ))
+            .all(&self.db)
+            .await
+            .unwrap_or_else(|x| {
+                log::error!("Error accessing the database: {:?}", x);
+                vec![]
+            })
+    }
+
+    /// Get rows whose entry defines the locution `text`, ignoring case and
+    /// written accents
+    pub async fn get_entries_of_locution(&self, text: &str) -> Vec<DleModel> {
+        Dle::find()
+            .from_raw_sql(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                r#"SELECT "dle".* FROM "dle" JOIN "dictionary" ON "dictionary"."code" = "dle"."source" WHERE ("dle"."lemma", "dle"."source") IN (SELECT "lemma", "source" FROM "locution" WHERE unaccent_lemma("locution") = unaccent_lemma($1)) ORDER BY "dictionary"."priority" ASC, "dle"."lemma" ASC LIMIT 10"#,
+                [text.into()],
+            ))
+            .all(&self.db)
+            .await
+            .unwrap_or_else(|x| {
+                log::error!("Error accessing the database: {:?}", x);
+                vec![]
+            })
+    }
+
+    /// Get list of `limit` locutions that start with `query`, ignoring case
+    /// and written accents
+    pub async fn get_locutions_like(&self, query: &str, limit: u64) -> Vec<LocutionModel> {
+        Locution::find()
+            .from_raw_sql(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                r#"SELECT * FROM "locution" WHERE unaccent_lemma("locution") LIKE unaccent_lemma($1) || '%' ORDER BY "locution" ASC LIMIT $2"#,
+                [query.into(), limit.into()],
+            ))
+            .all(&self.db)
+            .await
+            .unwrap_or_else(|x| {
+                log::error!("Error accessing the database: {:?}", x);
+                vec![]
+            })
+    }
+
+    /// Get the lemmas among `lemmas` that have an entry in some dictionary
+    pub async fn filter_existing_lemmas(&self, lemmas: &[String]) -> Vec<String> {
+        if lemmas.is_empty() {
+            return vec![];
+        }
+
+        Dle::find()
+            .select_only()
+            .column(dle::Column::Lemma)
+            .distinct()
+            .filter(dle::Column::Lemma.is_in(lemmas.iter().cloned()))
+            .into_model::<LemmaResult>()
+            .all(&self.db)
+            .await
+            .unwrap_or_else(|x| {
+                log::error!("Error accessing the database: {:?}", x);
+                vec![]
+            })
+            .into_iter()
+            .map(|result| result.lemma)
+            .collect()
+    }
+
+    /// Words among `words`, all lowercase, that are a lemma or one of its
+    /// inflected forms
+    pub async fn filter_known_words(&self, words: &[String]) -> Vec<String> {
+        if words.is_empty() {
+            return vec![];
+        }
+
+        let mut known = self.filter_existing_lemmas(words).await;
+        known.extend(
+            Inflection::find()
+                .select_only()
+                .column_as(inflection::Column::Form, "lemma")
+                .distinct()
+                .filter(inflection::Column::Form.is_in(words.iter().cloned()))
+                .into_model::<LemmaResult>()
+                .all(&self.db)
+                .await
+                .unwrap_or_else(|x| {
+                    log::error!("Error accessing the database: {:?}", x);
+                    vec![]
+                })
+                .into_iter()
+                .map(|result| result.lemma),
+        );
+
+        known
+    }
+
+    /// Get every abbreviation used in the definitions
+    pub async fn get_abbreviations(&self) -> Vec<AbbreviationModel> {
+        Abbreviation::find()
+            .all(&self.db)
+            .await
+            .unwrap_or_else(|x| {
+                log::error!("Error accessing the database: {:?}", x);
+                vec![]
+            })
+    }
+
+    /// Get the lemmas related to `lemma`, sorted by kind of relation.
+    /// Synonyms, antonyms and related words are symmetric, hypernyms aren't.
+    pub async fn get_relations(&self, lemma: &str) -> Vec<RelatedLemma> {
+        RelatedLemma::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            r#"SELECT "related", "kind"::text AS "kind" FROM (SELECT "related", "kind" FROM "relation" WHERE "lemma" = $1 UNION SELECT "lemma", "kind" FROM "relation" WHERE "related" = $1 AND "kind" <> 'hypernym') AS "relation" ORDER BY "relation"."kind" ASC, "related" ASC"#,
+            [lemma.into()],
+        ))
+        .all(&self.db)
+        .await
+        .unwrap_or_else(|x| {
+            log::error!("Error accessing the database: {:?}", x);
+            vec![]
+        })
+    }
+
+    /// Whether `lemma` has any related lemma, see `get_relations`
+    pub async fn has_relations(&self, lemma: &str) -> bool {
+        Relation::find()
+            .filter(
+                relation::Column::Lemma
+                    .eq(lemma)
+                    .or(relation::Column::Related
+                        .eq(lemma)
+                        .and(relation::Column::Kind.ne(RelationKind::Hypernym))),
+            )
+            .count(&self.db)
+            .await
+            .map(|count| count > 0)
+            .unwrap_or_else(|x| {
+                log::error!("Error accessing the database: {:?}", x);
+                false
+            })
+    }
+
+    /// Distinct lemmas other than `word` whose `column` equals `key`, sorted
+    async fn get_lemmas_sharing(
+        &self,
+        column: dle::Column,
+        key: &str,
+        word: &str,
+        excluded: Option<(dle::Column, &str)>,
+        limit: u64,
+    ) -> Vec<String> {
+        let mut select = Dle::find()
+            .select_only()
+            .column(dle::Column::Lemma)
+            .distinct()
+            .filter(column.eq(key))
+            .filter(Expr::cust_with_values(
+                r#"unaccent_lemma("lemma") <> unaccent_lemma($1)"#,
+                [word],
+            ));
+        if let Some((column, value)) = excluded {
+            select = select.filter(column.ne(value));
+        }
+
+        select
+            .order_by_asc(dle::Column::Lemma)
+            .limit(limit)
+            .into_model::<LemmaResult>()
+            .all(&self.db)
+            .await
+            .unwrap_or_else(|x| {
+                log::error!("Error accessing the database: {:?}", x);
+                vec![]
+            })
+            .into_iter()
+            .map(|result| result.lemma)
+            .collect()
+    }
+
+    /// Get the lemmas with the same letters as `word`, see `anagram_key`
+    pub async fn get_anagrams(&self, word: &str, limit: u64) -> Vec<String> {
+        self.get_lemmas_sharing(
+            dle::Column::AnagramKey,
+            &anagram_key(word),
+            word,
+            None,
+            limit,
+        )
+        .await
+    }
+
+    /// Get the lemmas that rhyme with `word` in consonant rhyme
+    pub async fn get_consonant_rhymes(&self, word: &str, limit: u64) -> Vec<String> {
+        let Some(key) = consonant_rhyme(word) else {
+            return vec![];
+        };
+
+        self.get_lemmas_sharing(dle::Column::ConsonantRhyme, &key, word, None, limit)
+            .await
+    }
+
+    /// Get the lemmas that rhyme with `word` only in assonant rhyme, those
+    /// that also share the consonant rhyme are left out
+    pub async fn get_assonant_rhymes(&self, word: &str, limit: u64) -> Vec<String> {
+        let (Some(key), Some(consonant)) = (assonant_rhyme(word), consonant_rhyme(word)) else {
+            return vec![];
+        };
+
+        self.get_lemmas_sharing(
+            dle::Column::AssonantRhyme,
+            &key,
+            word,
+            Some((dle::Column::ConsonantRhyme, &consonant)),
+            limit,
+        )
+        .await
+    }
+
+    /// Get dictionary by its code
+    pub async fn get_dictionary(&self, code: &str) -> Option<DictionaryModel> {
+        Dictionary::find_by_id(code.to_string())
+            .one(&self.db)
+            .await
+            .unwrap_or_else(|x| {
+                log::error!("Error accessing the database: {:?}", x);
+                None
+            })
+    }
+
+    /// Get all dictionaries sorted by priority
+    pub async fn get_dictionaries(&self) -> Vec<DictionaryModel> {
+        Dictionary::find()
+            .order_by_asc(dictionary::Column::Priority)
+            .all(&self.db)
+            .await
+            .unwrap_or_else(|x| {
+                log::error!("Error accessing the database: {:?}", x);
+                vec![]
+            })
+    }
+
+    /// Get the dictionaries that define `lemma`, sorted by priority
+    pub async fn get_sources_of(&self, lemma: &str) -> Vec<DictionaryModel> {
+        Dictionary::find()
+            .from_raw_sql(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                r#"SELECT "dictionary".* FROM "dictionary" JOIN "dle" ON "dle"."source" = "dictionary"."code" WHERE "dle"."lemma" = $1 ORDER BY "dictionary"."priority" ASC"#,
+                [lemma.into()],
+            ))
+            .all(&self.db)
+            .await
+            .unwrap_or_else(|x| {
+                log::error!("Error accessing the database: {:?}", x);
+                vec![]
+            })
+    }
+
     /// Get random word
     pub async fn get_random(&self) -> Option<DleModel> {
         Dle::find()
@@ -144,16 +691,88 @@ impl DatabaseHandler {
             .await
     }
 
-    /// Get word of the day: select a random word that hasn't been WOTD and returns it
-    pub async fn get_word_of_the_day(&self) -> Result<DleModel, &'static str> {
-        let today = Local::now().date_naive();
+    /// Pick a word of the day for `date` if none was scheduled: first a word
+    /// of the pool that was never used, otherwise an entry of the default
+    /// dictionary that passes the quality filters. Returns the picked lemma,
+    /// `None` if `date` already had one or there was no candidate.
+    pub async fn pick_word_of_the_day(&self, date: NaiveDate) -> Result<Option<String>, DbErr> {
+        let txn = self.db.begin().await?;
+
+        // Several tasks may look for the word of the day at once
+        txn.execute(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            r#"SELECT pg_advisory_xact_lock($1)"#,
+            [WOTD_PICK_LOCK.into()],
+        ))
+        .await?;
+
+        if WordOfTheDay::find()
+            .filter(word_of_the_day::Column::Date.eq(date))
+            .one(&txn)
+            .await?
+            .is_some()
+        {
+            txn.commit().await?;
+            return Ok(None);
+        }
+
+        let pooled = LemmaResult::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            r#"SELECT "word_of_the_day"."lemma" FROM "word_of_the_day" JOIN "dle" ON "dle"."lemma" = "word_of_the_day"."lemma" AND "dle"."source" = $1 WHERE "word_of_the_day"."date" IS NULL ORDER BY random() LIMIT 1"#,
+            [DEFAULT_DICTIONARY.into()],
+        ))
+        .one(&txn)
+        .await?;
+
+        let picked = match pooled {
+            Some(result) => Some(result),
+            None => {
+                let excluded_marks = WOTD_EXCLUDED_MARKS
+                    .iter()
+                    .map(|mark| regex::escape(mark))
+                    .collect::<Vec<String>>()
+                    .join("|");
+                let last_allowed = date - chrono::Duration::days(WOTD_REPEAT_DAYS);
+
+                LemmaResult::find_by_statement(Statement::from_sql_and_values(
+                    DbBackend::Postgres,
+                    r#"SELECT "lemma" FROM "dle" WHERE "source" = $1 AND char_length("definition") BETWEEN $2 AND $3 AND char_length("lemma") >= $4 AND "lemma" = LOWER("lemma") AND "lemma" NOT LIKE '-%' AND "lemma" NOT LIKE '%-' AND "definition" !~* $5 AND NOT EXISTS (SELECT 1 FROM "word_of_the_day" WHERE "word_of_the_day"."lemma" = "dle"."lemma" AND "word_of_the_day"."date" > $6) ORDER BY random() LIMIT 1"#,
+                    [
+                        DEFAULT_DICTIONARY.into(),
+                        WOTD_MIN_DEFINITION_LENGTH.into(),
+                        WOTD_MAX_DEFINITION_LENGTH.into(),
+                        WOTD_MIN_LEMMA_LENGTH.into(),
+                        excluded_marks.into(),
+                        last_allowed.into(),
+                    ],
+                ))
+                .one(&txn)
+                .await?
+            }
+        };
+
+        if let Some(LemmaResult { lemma }) = &picked {
+            txn.execute(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                r#"INSERT INTO "word_of_the_day" ("lemma", "date") VALUES ($1, $2) ON CONFLICT ("lemma") DO UPDATE SET "date" = EXCLUDED."date""#,
+                [lemma.into(), date.into()],
+            ))
+            .await?;
+        }
 
-        // Get a word that has today's date
+        txn.commit().await?;
+
+        Ok(picked.map(|result| result.lemma))
+    }
+
+    /// Get the word of the day of `date`, if one was scheduled or picked
+    pub async fn get_word_of_the_day(&self, date: NaiveDate) -> Result<DleModel, &'static str> {
+        // Get a word that has that date
         match WordOfTheDay::find()
             .from_raw_sql(Statement::from_sql_and_values(
                 DbBackend::Postgres,
                 r#"SELECT * FROM "word_of_the_day" WHERE "date" = $1 LIMIT 1"#,
-                [today.into()],
+                [date.into()],
             ))
             .one(&self.db)
             .await
@@ -166,13 +785,205 @@ impl DatabaseHandler {
                     return Ok(result);
                 }
             }
-            None => return Err("No word of the day for today"),
+            None => return Err("No word of the day for that date"),
         }
 
         Err("Error obtaining word of the day")
     }
 }
 
+/// Index implementations
+impl DatabaseHandler {
+    /// Rebuild the "inflection" table from the dictionary.
+    /// Returns the number of indexed forms.
+    pub async fn rebuild_inflection_index(&self) -> Result<usize, DbErr> {
+        let txn = self.db.begin().await?;
+        Inflection::delete_many().exec(&txn).await?;
+
+        let mut pages = Dle::find()
+            .order_by_asc(dle::Column::Lemma)
+            .order_by_asc(dle::Column::Source)
+            .paginate(&txn, INDEX_PAGE_SIZE);
+        let mut count = 0;
+
+        while let Some(words) = pages.fetch_and_next().await? {
+            let rows: Vec<inflection::ActiveModel> = words
+                .iter()
+                .flat_map(|word| {
+                    inflected_forms(word)
+                        .into_iter()
+                        .map(|form| inflection::ActiveModel {
+                            form: Set(form),
+                            lemma: Set(word.lemma.clone()),
+                        })
+                })
+                .collect();
+
+            for batch in rows.chunks(INDEX_INSERT_BATCH_SIZE) {
+                Inflection::insert_many(batch.to_vec())
+                    .on_conflict(
+                        OnConflict::columns([inflection::Column::Form, inflection::Column::Lemma])
+                            .do_nothing()
+                            .to_owned(),
+                    )
+                    .exec_without_returning(&txn)
+                    .await?;
+            }
+
+            count += rows.len();
+        }
+
+        txn.commit().await?;
+
+        Ok(count)
+    }
+
+    /// Rebuild the "phonetic" table from the dictionary.
+    /// Returns the number of indexed lemmas.
+    pub async fn rebuild_phonetic_index(&self) -> Result<usize, DbErr> {
+        let txn = self.db.begin().await?;
+        Phonetic::delete_many().exec(&txn).await?;
+
+        let mut pages = Dle::find()
+            .order_by_asc(dle::Column::Lemma)
+            .order_by_asc(dle::Column::Source)
+            .paginate(&txn, INDEX_PAGE_SIZE);
+        let mut count = 0;
+
+        while let Some(words) = pages.fetch_and_next().await? {
+            let rows: Vec<phonetic::ActiveModel> = words
+                .iter()
+                .map(|word| phonetic::ActiveModel {
+                    lemma: Set(word.lemma.clone()),
+                    key: Set(phonetic_key(&word.lemma)),
+                })
+                .collect();
+
+            for batch in rows.chunks(INDEX_INSERT_BATCH_SIZE) {
+                Phonetic::insert_many(batch.to_vec())
+                    .on_conflict(
+                        OnConflict::column(phonetic::Column::Lemma)
+                            .do_nothing()
+                            .to_owned(),
+                    )
+                    .exec_without_returning(&txn)
+                    .await?;
+            }
+
+            count += rows.len();
+        }
+
+        txn.commit().await?;
+
+        Ok(count)
+    }
+
+    /// Fill the anagram and rhyme keys of every entry of the dictionary.
+    /// Returns the number of updated entries.
+    pub async fn rebuild_word_game_keys(&self) -> Result<usize, DbErr> {
+        let mut pages = Dle::find()
+            .order_by_asc(dle::Column::Lemma)
+            .order_by_asc(dle::Column::Source)
+            .paginate(&self.db, INDEX_PAGE_SIZE);
+        let mut count = 0;
+
+        while let Some(words) = pages.fetch_and_next().await? {
+            let mut rows: Vec<String> = vec![];
+            let mut values: Vec<Value> = vec![];
+
+            for word in &words {
+                let start = values.len();
+                rows.push(format!(
+                    "(${}, ${}, ${}, ${}, ${})",
+                    start + 1,
+                    start + 2,
+                    start + 3,
+                    start + 4,
+                    start + 5
+                ));
+                values.extend([
+                    word.lemma.clone().into(),
+                    word.source.clone().into(),
+                    anagram_key(&word.lemma).into(),
+                    consonant_rhyme(&word.lemma).into(),
+                    assonant_rhyme(&word.lemma).into(),
+                ]);
+            }
+
+            if rows.is_empty() {
+                continue;
+            }
+
+            self.db
+                .execute(Statement::from_sql_and_values(
+                    DbBackend::Postgres,
+                    &format!(
+                        r#"UPDATE "dle" SET "anagram_key" = "keys"."anagram_key", "consonant_rhyme" = "keys"."consonant_rhyme", "assonant_rhyme" = "keys"."assonant_rhyme" FROM (VALUES {}) AS "keys" ("lemma", "source", "anagram_key", "consonant_rhyme", "assonant_rhyme") WHERE "dle"."lemma" = "keys"."lemma" AND "dle"."source" = "keys"."source""#,
+                        rows.join(", ")
+                    ),
+                    values,
+                ))
+                .await?;
+
+            count += words.len();
+        }
+
+        Ok(count)
+    }
+
+    /// Rebuild the "locution" table from the locutions defined in the entries
+    /// of the dictionary. Returns the number of indexed locutions.
+    pub async fn rebuild_locution_index(&self) -> Result<usize, DbErr> {
+        let txn = self.db.begin().await?;
+        Locution::delete_many().exec(&txn).await?;
+
+        let mut pages = Dle::find()
+            .order_by_asc(dle::Column::Lemma)
+            .order_by_asc(dle::Column::Source)
+            .paginate(&txn, INDEX_PAGE_SIZE);
+        let mut count = 0;
+
+        while let Some(words) = pages.fetch_and_next().await? {
+            let rows: Vec<locution::ActiveModel> = words
+                .iter()
+                .flat_map(|word| {
+                    Entry::parse(&word.definition)
+                        .map(|entry| entry.locutions)
+                        .unwrap_or_default()
+                        .into_iter()
+                        .filter(|locution| !locution.lemma.is_empty())
+                        .map(|locution| locution::ActiveModel {
+                            locution: Set(locution.lemma),
+                            lemma: Set(word.lemma.clone()),
+                            source: Set(word.source.clone()),
+                        })
+                })
+                .collect();
+
+            for batch in rows.chunks(INDEX_INSERT_BATCH_SIZE) {
+                Locution::insert_many(batch.to_vec())
+                    .on_conflict(
+                        OnConflict::columns([
+                            locution::Column::Locution,
+                            locution::Column::Lemma,
+                            locution::Column::Source,
+                        ])
+                        .do_nothing()
+                        .to_owned(),
+                    )
+                    .exec_without_returning(&txn)
+                    .await?;
+            }
+
+            count += rows.len();
+        }
+
+        txn.commit().await?;
+
+        Ok(count)
+    }
+}
+
 /// User implementations
 impl DatabaseHandler {
     /// Get user
@@ -195,13 +1006,14 @@ impl DatabaseHandler {
             .unwrap_or_default()
     }
 
-    /// Get list of subscribed users
-    pub async fn _get_subscribed_and_in_bot_list(&self) -> Vec<i64> {
+    /// Get the users that get the word of the day
+    pub async fn get_wotd_subscribers(&self) -> Vec<user::Model> {
         User::find()
             .filter(
                 user::Column::Subscribed
                     .eq(true)
-                    .and(user::Column::InBot.eq(true)),
+                    .and(user::Column::InBot.eq(true))
+                    .and(user::Column::Blocked.eq(false)),
             )
             .all(&self.db)
             .await
@@ -209,6 +1021,18 @@ impl DatabaseHandler {
                 log::error!("Error accessing the database: {:?}", x);
                 vec![]
             })
+    }
+
+    /// Get the ids of the admins
+    pub async fn get_admins(&self) -> Vec<i64> {
+        User::find()
+            .filter(user::Column::Admin.eq(true))
+            .all(&self.db)
+            .await
+            .unwrap_or_else(|x| {
+                log::error!("Error accessing the database: {:?}", x);
+                vec![]
+            })
             .iter()
             .map(|m| m.id)
             .collect()
@@ -229,28 +1053,46 @@ impl DatabaseHandler {
             .collect()
     }
 
+    /// Change the user with `change`, adding it first with the default
+    /// settings if it's not in the database
+    async fn update_user(&self, user_id: i64, change: impl FnOnce(&mut user::ActiveModel)) {
+        let result = match self.get_user(user_id).await {
+            Some(user) => {
+                let mut user: user::ActiveModel = user.into();
+                change(&mut user);
+                user.update(&self.db).await.map(|_| ())
+            }
+            None => {
+                let mut user: user::ActiveModel = user::Model {
+                    id: user_id,
+                    subscribed: false,
+                    blocked: false,
+                    in_bot: true,
+                    admin: false,
+                    output_format: OutputFormat::Full,
+                    show_syllables: false,
+                    delivery_time: default_delivery_time(),
+                    timezone: DEFAULT_TIMEZONE.to_string(),
+                    inline_dictionary: None,
+                }
+                .into();
+                change(&mut user);
+                user.insert(&self.db).await.map(|_| ())
+            }
+        };
+
+        if let Err(x) = result {
+            log::error!("Error accessing the database: {:?}", x);
+        }
+    }
+
     /// Set subscribed status
     pub async fn set_subscribed(&self, user_id: i64, subscribed: bool) {
-        if let Some(user) = self.get_user(user_id).await {
-            let mut user: user::ActiveModel = user.into();
+        self.update_user(user_id, |user| {
             user.subscribed = Set(subscribed);
             user.in_bot = Set(true);
-            if let Err(x) = user.update(&self.db).await {
-                log::error!("Error accessing the database: {:?}", x);
-            }
-        } else {
-            let new_user = user::Model {
-                id: user_id,
-                subscribed,
-                blocked: false,
-                in_bot: true,
-                admin: false,
-            };
-            let new_user: user::ActiveModel = new_user.into();
-            if let Err(x) = new_user.insert(&self.db).await {
-                log::error!("Error accessing the database: {:?}", x);
-            }
-        }
+        })
+        .await;
     }
 
     /// Set blocked status
@@ -277,6 +1119,109 @@ impl DatabaseHandler {
         }
     }
 
+    /// Get the format in which definitions are sent to the user
+    pub async fn get_output_format(&self, user_id: i64) -> OutputFormat {
+        self.get_user(user_id)
+            .await
+            .map(|user| user.output_format)
+            .unwrap_or(OutputFormat::Full)
+    }
+
+    /// Set the format in which definitions are sent to the user
+    pub async fn set_output_format(&self, user_id: i64, output_format: OutputFormat) {
+        self.update_user(user_id, |user| user.output_format = Set(output_format))
+            .await;
+    }
+
+    /// Get whether definitions sent to the user end with the syllables of the
+    /// lemma
+    pub async fn get_show_syllables(&self, user_id: i64) -> bool {
+        self.get_user(user_id)
+            .await
+            .is_some_and(|user| user.show_syllables)
+    }
+
+    /// Set whether definitions sent to the user end with the syllables of the
+    /// lemma
+    pub async fn set_show_syllables(&self, user_id: i64, show_syllables: bool) {
+        self.update_user(user_id, |user| user.show_syllables = Set(show_syllables))
+            .await;
+    }
+
+    /// Get the dictionary searched by the inline mode when the query doesn't
+    /// name one, `None` for all of them
+    pub async fn get_inline_dictionary(&self, user_id: i64) -> Option<String> {
+        self.get_user(user_id)
+            .await
+            .and_then(|user| user.inline_dictionary)
+    }
+
+    /// Set the dictionary searched by the inline mode when the query doesn't
+    /// name one, `None` for all of them
+    pub async fn set_inline_dictionary(&self, user_id: i64, inline_dictionary: Option<String>) {
+        self.update_user(user_id, |user| {
+            user.inline_dictionary = Set(inline_dictionary)
+        })
+        .await;
+    }
+
+    /// Get the local time and time zone at which the user gets the word of
+    /// the day
+    pub async fn get_delivery_settings(&self, user_id: i64) -> (NaiveTime, String) {
+        self.get_user(user_id)
+            .await
+            .map(|user| (user.delivery_time, user.timezone))
+            .unwrap_or((default_delivery_time(), DEFAULT_TIMEZONE.to_string()))
+    }
+
+    /// Set the local time and time zone at which the user gets the word of
+    /// the day
+    pub async fn set_delivery_settings(&self, user_id: i64, time: NaiveTime, timezone: &str) {
+        self.update_user(user_id, |user| {
+            user.delivery_time = Set(time);
+            user.timezone = Set(timezone.to_string());
+        })
+        .await;
+    }
+
+    /// Record that the word of the day of the local `date` is being sent to
+    /// the user. Returns `false` if it was already recorded, so it must not
+    /// be sent again.
+    pub async fn claim_wotd_delivery(&self, user_id: i64, date: NaiveDate) -> bool {
+        let delivery = wotd_delivery::ActiveModel {
+            user_id: Set(user_id),
+            date: Set(date),
+            sent_at: Set(Utc::now().into()),
+        };
+
+        WotdDelivery::insert(delivery)
+            .on_conflict(
+                OnConflict::columns([wotd_delivery::Column::UserId, wotd_delivery::Column::Date])
+                    .do_nothing()
+                    .to_owned(),
+            )
+            .exec_without_returning(&self.db)
+            .await
+            .unwrap_or_else(|x| {
+                log::error!("Error accessing the database: {:?}", x);
+                0
+            })
+            == 1
+    }
+
+    /// Undo `claim_wotd_delivery` when the word of the day couldn't be sent,
+    /// so it's tried again
+    pub async fn release_wotd_delivery(&self, user_id: i64, date: NaiveDate) {
+        if let Err(x) = WotdDelivery::delete_many()
+            .filter(wotd_delivery::Column::UserId.eq(user_id))
+            .filter(wotd_delivery::Column::Date.eq(date))
+            .exec(&self.db)
+            .await
+        {
+            log::error!("Error accessing the database: {:?}", x);
+        }
+    }
+
     /// Set admin status
     /// TODO:
     pub async fn _set_admin(&self, user_id: i64, admin: bool) {
@@ -290,6 +1235,200 @@ impl DatabaseHandler {
     }
 }
 
+/// Chat implementations
+impl DatabaseHandler {
+    /// Get the settings of a group, the defaults if they were never changed
+    pub async fn get_chat(&self, chat_id: i64) -> ChatModel {
+        Chat::find_by_id(chat_id)
+            .one(&self.db)
+            .await
+            .unwrap_or_else(|x| {
+                log::error!("Error accessing the database: {:?}", x);
+                None
+            })
+            .unwrap_or(ChatModel {
+                id: chat_id,
+                commands_enabled: true,
+                wotd_enabled: false,
+            })
+    }
+
+    /// Save the settings of a group
+    pub async fn set_chat(&self, chat: ChatModel) {
+        let chat: chat::ActiveModel = chat.into();
+        if let Err(x) = Chat::insert(chat)
+            .on_conflict(
+                OnConflict::column(chat::Column::Id)
+                    .update_columns([chat::Column::CommandsEnabled, chat::Column::WotdEnabled])
+                    .to_owned(),
+            )
+            .exec_without_returning(&self.db)
+            .await
+        {
+            log::error!("Error accessing the database: {:?}", x);
+        }
+    }
+
+    /// Forget the settings of a group the bot is no longer in
+    pub async fn remove_chat(&self, chat_id: i64) {
+        if let Err(x) = Chat::delete_by_id(chat_id).exec(&self.db).await {
+            log::error!("Error accessing the database: {:?}", x);
+        }
+    }
+
+    /// Get the groups where the word of the day is posted
+    pub async fn get_wotd_chats(&self) -> Vec<i64> {
+        Chat::find()
+            .filter(chat::Column::WotdEnabled.eq(true))
+            .all(&self.db)
+            .await
+            .unwrap_or_else(|x| {
+                log::error!("Error accessing the database: {:?}", x);
+                vec![]
+            })
+            .into_iter()
+            .map(|chat| chat.id)
+            .collect()
+    }
+
+    /// Record that the word of the day of the local `date` is being posted
+    /// in the chat. Returns `false` if it was already recorded, so it must
+    /// not be posted again.
+    pub async fn claim_wotd_post(&self, chat_id: i64, date: NaiveDate) -> bool {
+        let post = wotd_post::ActiveModel {
+            chat_id: Set(chat_id),
+            date: Set(date),
+            sent_at: Set(Utc::now().into()),
+        };
+
+        WotdPost::insert(post)
+            .on_conflict(
+                OnConflict::columns([wotd_post::Column::ChatId, wotd_post::Column::Date])
+                    .do_nothing()
+                    .to_owned(),
+            )
+            .exec_without_returning(&self.db)
+            .await
+            .unwrap_or_else(|x| {
+                log::error!("Error accessing the database: {:?}", x);
+                0
+            })
+            == 1
+    }
+
+    /// Undo `claim_wotd_post` when the word of the day couldn't be posted,
+    /// so it's tried again
+    pub async fn release_wotd_post(&self, chat_id: i64, date: NaiveDate) {
+        if let Err(x) = WotdPost::delete_many()
+            .filter(wotd_post::Column::ChatId.eq(chat_id))
+            .filter(wotd_post::Column::Date.eq(date))
+            .exec(&self.db)
+            .await
+        {
+            log::error!("Error accessing the database: {:?}", x);
+        }
+    }
+}
+
+/// Word of the day target implementations
+impl DatabaseHandler {
+    /// Get the chats where the word of the day is posted on its own schedule
+    pub async fn get_wotd_targets(&self) -> Vec<WotdTargetModel> {
+        WotdTarget::find()
+            .order_by_asc(wotd_target::Column::ChatId)
+            .all(&self.db)
+            .await
+            .unwrap_or_else(|x| {
+                log::error!("Error accessing the database: {:?}", x);
+                vec![]
+            })
+    }
+
+    /// Get the schedule and style of the word of the day in a chat
+    pub async fn get_wotd_target(&self, chat_id: i64) -> Option<WotdTargetModel> {
+        WotdTarget::find_by_id(chat_id)
+            .one(&self.db)
+            .await
+            .unwrap_or_else(|x| {
+                log::error!("Error accessing the database: {:?}", x);
+                None
+            })
+    }
+
+    /// Save the schedule and style of the word of the day in a chat
+    pub async fn set_wotd_target(&self, target: WotdTargetModel) {
+        let target: wotd_target::ActiveModel = target.into();
+        if let Err(x) = WotdTarget::insert(target)
+            .on_conflict(
+                OnConflict::column(wotd_target::Column::ChatId)
+                    .update_columns([
+                        wotd_target::Column::DeliveryTime,
+                        wotd_target::Column::Timezone,
+                        wotd_target::Column::Theme,
+                        wotd_target::Column::Language,
+                        wotd_target::Column::Enabled,
+                    ])
+                    .to_owned(),
+            )
+            .exec_without_returning(&self.db)
+            .await
+        {
+            log::error!("Error accessing the database: {:?}", x);
+        }
+    }
+
+    /// Stop posting the word of the day in a chat. Returns whether it was a
+    /// target.
+    pub async fn remove_wotd_target(&self, chat_id: i64) -> bool {
+        WotdTarget::delete_by_id(chat_id)
+            .exec(&self.db)
+            .await
+            .map(|result| result.rows_affected > 0)
+            .unwrap_or_else(|x| {
+                log::error!("Error accessing the database: {:?}", x);
+                false
+            })
+    }
+}
+
+/// Scheduler implementations
+impl DatabaseHandler {
+    /// Get the last time the job `name` ran
+    pub async fn get_last_run(&self, name: &str) -> Option<DateTimeWithTimeZone> {
+        ScheduledJob::find_by_id(name.to_string())
+            .one(&self.db)
+            .await
+            .unwrap_or_else(|x| {
+                log::error!("Error accessing the database: {:?}", x);
+                None
+            })
+            .and_then(|job| job.last_run)
+    }
+
+    /// Record that the job `name` runs at `now` for its run scheduled at
+    /// `due`. Returns `false` if it already ran since then, so it must not
+    /// run again.
+    pub async fn claim_scheduled_job(
+        &self,
+        name: &str,
+        due: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> bool {
+        self.db
+            .execute(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                r#"INSERT INTO "scheduled_job" ("name", "last_run") VALUES ($1, $2) ON CONFLICT ("name") DO UPDATE SET "last_run" = EXCLUDED."last_run" WHERE "scheduled_job"."last_run" IS NULL OR "scheduled_job"."last_run" < $3"#,
+                [name.into(), now.into(), due.into()],
+            ))
+            .await
+            .map(|result| result.rows_affected() == 1)
+            .unwrap_or_else(|x| {
+                log::error!("Error accessing the database: {:?}", x);
+                false
+            })
+    }
+}
+
 /// Event implementations
 impl DatabaseHandler {
     pub async fn add_message_event(
@@ -11,6 +11,11 @@ pub struct Model {
     pub definition: String,
     #[sea_orm(column_type = "JsonBinary", nullable)]
     pub conjugation: Option<Json>,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub source: String,
+    pub anagram_key: Option<String>,
+    pub consonant_rhyme: Option<String>,
+    pub assonant_rhyme: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -2,8 +2,19 @@
 
 pub mod prelude;
 
+pub mod abbreviation;
+pub mod chat;
+pub mod dictionary;
 pub mod dle;
 pub mod event;
+pub mod inflection;
+pub mod locution;
+pub mod phonetic;
+pub mod relation;
+pub mod scheduled_job;
 pub mod sea_orm_active_enums;
 pub mod user;
 pub mod word_of_the_day;
+pub mod wotd_delivery;
+pub mod wotd_post;
+pub mod wotd_target;
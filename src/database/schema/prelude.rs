@@ -1,5 +1,16 @@
 //! `SeaORM` Entity. Generated by sea-orm-codegen 0.11.3
 
+pub use super::abbreviation::Entity as Abbreviation;
+pub use super::chat::Entity as Chat;
+pub use super::dictionary::Entity as Dictionary;
 pub use super::dle::Entity as Dle;
+pub use super::inflection::Entity as Inflection;
+pub use super::locution::Entity as Locution;
+pub use super::phonetic::Entity as Phonetic;
+pub use super::relation::Entity as Relation;
+pub use super::scheduled_job::Entity as ScheduledJob;
 pub use super::user::Entity as User;
 pub use super::word_of_the_day::Entity as WordOfTheDay;
+pub use super::wotd_delivery::Entity as WotdDelivery;
+pub use super::wotd_post::Entity as WotdPost;
+pub use super::wotd_target::Entity as WotdTarget;
@@ -20,3 +20,27 @@ pub enum EventType {
     #[sea_orm(string_value = "user_left")]
     UserLeft,
 }
+
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "output_format")]
+pub enum OutputFormat {
+    #[sea_orm(string_value = "compact")]
+    Compact,
+    #[sea_orm(string_value = "full")]
+    Full,
+    #[sea_orm(string_value = "image")]
+    Image,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "relation_kind")]
+pub enum RelationKind {
+    #[sea_orm(string_value = "antonym")]
+    Antonym,
+    #[sea_orm(string_value = "hypernym")]
+    Hypernym,
+    #[sea_orm(string_value = "related")]
+    Related,
+    #[sea_orm(string_value = "synonym")]
+    Synonym,
+}
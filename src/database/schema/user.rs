@@ -1,5 +1,6 @@
 //! `SeaORM` Entity. Generated by sea-orm-codegen 0.11.3
 
+use super::sea_orm_active_enums::OutputFormat;
 use sea_orm::entity::prelude::*;
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
@@ -11,6 +12,11 @@ pub struct Model {
     pub blocked: bool,
     pub in_bot: bool,
     pub admin: bool,
+    pub output_format: OutputFormat,
+    pub show_syllables: bool,
+    pub delivery_time: Time,
+    pub timezone: String,
+    pub inline_dictionary: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -0,0 +1,227 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Duration, NaiveDate, NaiveTime, Timelike, Utc};
+use chrono_tz::Tz;
+use teloxide::{prelude::*, ApiError, RequestError};
+use tokio::time::{interval_at, Duration as StdDuration, Instant};
+
+use crate::{
+    database::{DatabaseHandler, DEFAULT_TIMEZONE},
+    image::send_image,
+    utils::DISABLED_LINK_PREVIEW,
+    word_of_the_day::word_of_the_day_for,
+    DLEBot,
+};
+
+/// Minutes after the chosen time during which the word of the day can still
+/// be sent, so it isn't lost if the bot was down or the time was skipped by
+/// a daylight saving time change
+const DELIVERY_WINDOW_MINUTES: i64 = 60;
+const TIMEZONES_URL: &str = "https://en.wikipedia.org/wiki/List_of_tz_database_time_zones";
+
+/// Time zone named `name`, or the default one if it isn't valid
+pub fn timezone_or_default(name: &str) -> Tz {
+    name.parse()
+        .or_else(|_| DEFAULT_TIMEZONE.parse())
+        .unwrap_or(Tz::UTC)
+}
+
+/// Local date whose word of the day is due at `now` for a user that gets it
+/// at `time` in `timezone`, if any
+fn due_date(now: DateTime<Utc>, timezone: Tz, time: NaiveTime) -> Option<NaiveDate> {
+    let local = now.with_timezone(&timezone).naive_local();
+    let today = local.date();
+
+    // The window of yesterday may still be open a bit after midnight
+    [Some(today), today.pred_opt()]
+        .into_iter()
+        .flatten()
+        .find(|date| {
+            let elapsed = local - date.and_time(time);
+            elapsed >= Duration::zero() && elapsed < Duration::minutes(DELIVERY_WINDOW_MINUTES)
+        })
+}
+
+/// Parse the arguments of `/hora`: a time like `8:30` or `20`, optionally
+/// followed by a time zone like `America/Havana`
+pub fn parse_delivery_settings(text: &str) -> Option<(NaiveTime, Option<Tz>)> {
+    let mut args = text.split_whitespace();
+
+    let time = args.next()?;
+    let time = NaiveTime::parse_from_str(time, "%H:%M")
+        .ok()
+        .or_else(|| NaiveTime::from_hms_opt(time.parse().ok()?, 0, 0))?;
+
+    let timezone = match args.next() {
+        Some(timezone) => Some(timezone.parse().ok()?),
+        None => None,
+    };
+    if args.next().is_some() {
+        return None;
+    }
+
+    Some((time, timezone))
+}
+
+/// Show or change the time and time zone at which the user gets the word of
+/// the day
+pub async fn send_delivery_settings(
+    db_handler: &DatabaseHandler,
+    bot: &DLEBot,
+    chat_id: ChatId,
+    user_id: i64,
+    text: &str,
+) -> ResponseResult<()> {
+    let (time, timezone) = db_handler.get_delivery_settings(user_id).await;
+
+    if text.trim().is_empty() {
+        bot.send_message(
+            chat_id,
+            format!(
+                "Recibes la 📖 <i>Palabra del día</i> a las {} ({}) si estás suscrito.\n\nPara cambiarlo usa /hora seguido de la hora y, si quieres, de <a href=\"{}\">tu zona horaria</a>, por ejemplo: <code>/hora 8:30 America/Havana</code>",
+                time.format("%H:%M"),
+                timezone,
+                TIMEZONES_URL
+            ),
+        )
+        .link_preview_options(DISABLED_LINK_PREVIEW)
+        .await?;
+        return Ok(());
+    }
+
+    let Some((time, new_timezone)) = parse_delivery_settings(text) else {
+        bot.send_message(
+            chat_id,
+            format!(
+                "No entendí la hora. Escríbela como <code>8:30</code> o <code>20</code>, y si quieres añade <a href=\"{}\">tu zona horaria</a>, por ejemplo: <code>/hora 8:30 America/Havana</code>",
+                TIMEZONES_URL
+            ),
+        )
+        .link_preview_options(DISABLED_LINK_PREVIEW)
+        .await?;
+        return Ok(());
+    };
+
+    let timezone = new_timezone.map_or(timezone, |timezone| timezone.name().to_string());
+    db_handler
+        .set_delivery_settings(user_id, time, &timezone)
+        .await;
+
+    bot.send_message(
+        chat_id,
+        format!(
+            "✅ Recibirás la 📖 <i>Palabra del día</i> a las {} ({}).",
+            time.format("%H:%M"),
+            timezone
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Send the word of the day to every subscriber whose time has come and
+/// hasn't got it yet, the word of the date in their time zone
+async fn deliver_word_of_the_day(db_handler: &DatabaseHandler, bot: &DLEBot) {
+    let now = Utc::now();
+    let mut due: BTreeMap<NaiveDate, Vec<i64>> = BTreeMap::new();
+    for user in db_handler.get_wotd_subscribers().await {
+        let timezone = timezone_or_default(&user.timezone);
+        if let Some(date) = due_date(now, timezone, user.delivery_time) {
+            due.entry(date).or_default().push(user.id);
+        }
+    }
+
+    for (date, user_ids) in due {
+        let Some(wotd) = word_of_the_day_for(db_handler, bot, date).await else {
+            continue;
+        };
+
+        for user_id in user_ids {
+            if !db_handler.claim_wotd_delivery(user_id, date).await {
+                continue;
+            }
+
+            match send_image(wotd.clone(), bot.clone(), ChatId(user_id), true).await {
+                Ok(true) => {}
+                // Tried again next minute while the window is open
+                Ok(false) => db_handler.release_wotd_delivery(user_id, date).await,
+                Err(RequestError::Api(ApiError::BotBlocked | ApiError::UserDeactivated)) => {
+                    db_handler.set_in_bot(user_id, false).await;
+                }
+                Err(error) => {
+                    log::warn!(
+                        "Error while sending word of the day to {}: {:?}",
+                        user_id,
+                        error
+                    );
+                    // Tried again next minute while the window is open
+                    db_handler.release_wotd_delivery(user_id, date).await;
+                }
+            }
+        }
+    }
+}
+
+/// Check every minute which subscribers are due to get the word of the day
+pub async fn schedule_deliveries(db_handler: DatabaseHandler, bot: DLEBot) {
+    let initial_delay = StdDuration::from_secs((60 - Utc::now().second()).into());
+    let mut interval = interval_at(Instant::now() + initial_delay, StdDuration::from_secs(60));
+
+    log::info!("Word of the day deliveries scheduled!");
+
+    loop {
+        interval.tick().await;
+        deliver_word_of_the_day(&db_handler, &bot).await;
+    }
+}
+
+#[test]
+fn test_due_date() {
+    let madrid: Tz = "Europe/Madrid".parse().unwrap();
+    let time = NaiveTime::from_hms_opt(9, 0, 0).unwrap();
+    let utc = |text: &str| text.parse::<DateTime<Utc>>().unwrap();
+    let date = |text: &str| text.parse::<NaiveDate>().ok();
+
+    // 09:00 in Madrid is 07:00 UTC in summer and 08:00 UTC in winter
+    assert_eq!(
+        due_date(utc("2026-07-01T07:00:00Z"), madrid, time),
+        date("2026-07-01")
+    );
+    assert_eq!(due_date(utc("2026-12-01T07:00:00Z"), madrid, time), None);
+    assert_eq!(
+        due_date(utc("2026-12-01T08:59:00Z"), madrid, time),
+        date("2026-12-01")
+    );
+    assert_eq!(due_date(utc("2026-12-01T09:00:00Z"), madrid, time), None);
+
+    // 02:30 doesn't exist on the day clocks go forward, so it's sent at 03:00
+    let time = NaiveTime::from_hms_opt(2, 30, 0).unwrap();
+    assert_eq!(
+        due_date(utc("2026-03-29T01:00:00Z"), madrid, time),
+        date("2026-03-29")
+    );
+
+    // The window of 23:30 ends after midnight
+    let time = NaiveTime::from_hms_opt(23, 30, 0).unwrap();
+    assert_eq!(
+        due_date(utc("2026-12-01T23:10:00Z"), madrid, time),
+        date("2026-12-01")
+    );
+}
+
+#[test]
+fn test_parse_delivery_settings() {
+    let time = |hour, min| NaiveTime::from_hms_opt(hour, min, 0).unwrap();
+
+    assert_eq!(parse_delivery_settings("8:30"), Some((time(8, 30), None)));
+    assert_eq!(parse_delivery_settings("20"), Some((time(20, 0), None)));
+    assert_eq!(
+        parse_delivery_settings(" 07:05  America/Havana "),
+        Some((time(7, 5), Some(Tz::America__Havana)))
+    );
+    assert_eq!(parse_delivery_settings("25:00"), None);
+    assert_eq!(parse_delivery_settings("8:30 Marte/Olympus"), None);
+    assert_eq!(parse_delivery_settings("8:30 UTC extra"), None);
+    assert_eq!(parse_delivery_settings("mañana"), None);
+}
@@ -0,0 +1,456 @@
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use crate::utils::unaccent;
+
+static TAGS: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"<[^>]*>").unwrap());
+/// Line of a numbered sense, like `<b>1.</b> <i>f.</i> Edificio para habitar.`
+static SENSE_LINE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(?:<b>)?\s*(\d+)\s*\.\s*(?:</b>)?\s*(.*)$").unwrap());
+/// Grammar tags of a sense, in italics or as plain text. Plain tags start
+/// with a lowercase letter so the first word of the text isn't taken as one.
+static ITALIC_TAG: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s*<i>\s*([^<]*?)\s*</i>").unwrap());
+static PLAIN_TAG: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\s*(\p{Ll}\p{L}{0,5}\.(?:\s+(?:(?:y|o|u)\s+)?\p{Ll}\p{L}{0,5}\.)*)(?:\s|$)")
+        .unwrap()
+});
+static ABBREVIATIONS: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(?:\p{L}{1,6}\.(?:\s+(?:y|o|u)\s+|\s+|$))+$").unwrap());
+/// Examples in italics at the end of a sense
+static TRAILING_EXAMPLES: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(.*?[.:!?])((?:\s*<i>[^<]*</i>)+)\s*$").unwrap());
+static EXAMPLE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"<i>([^<]*)</i>").unwrap());
+
+/// Structured form of the `dle.definition` column.
+///
+/// Definitions are stored as Telegram HTML, one line per sense, like the
+/// articles of the DLE:
+///
+/// ```text
+/// <b>casa</b>
+///
+/// Del lat. casa 'choza'.
+///
+/// <b>1.</b> <i>f.</i> Edificio para habitar. <i>Una casa de ocho plantas.</i>
+/// <b>2.</b> <i>f.</i> Edificio, piso o apartamento destinado a vivienda.
+///
+/// <b>casa de socorro</b>
+/// <b>1.</b> <i>f.</i> Establecimiento sanitario donde se prestan los primeros auxilios.
+/// ```
+///
+/// Lines that are neither the header, the etymology, a sense nor the name of
+/// a locution (synonyms, references, usage notes) are kept in the text of the
+/// previous sense.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+    /// Header of the article as plain text, like `niño, ña`
+    pub lemma: String,
+    pub etymology: Option<String>,
+    pub senses: Vec<Sense>,
+    pub locutions: Vec<Locution>,
+}
+
+/// One *acepción*
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sense {
+    pub number: u32,
+    /// Groups of abbreviations as written before the text, like `m. y f.`
+    pub grammar_tags: Vec<String>,
+    pub text: String,
+    pub examples: Vec<String>,
+}
+
+/// Complex form (*locución*, *expresión*) defined inside an article
+#[derive(Debug, Clone, PartialEq)]
+pub struct Locution {
+    pub lemma: String,
+    pub senses: Vec<Sense>,
+}
+
+/// Remove the HTML tags of `text`
+pub fn strip_tags(text: &str) -> String {
+    TAGS.replace_all(text, "").trim().to_string()
+}
+
+impl Sense {
+    fn parse(number: u32, line: &str) -> Self {
+        let mut rest = line.trim();
+        let mut grammar_tags: Vec<String> = vec![];
+        // Tags are either all in italics or all plain
+        let mut italics = false;
+
+        loop {
+            if let Some(captures) = ITALIC_TAG.captures(rest) {
+                if ABBREVIATIONS.is_match(&captures[1]) {
+                    grammar_tags.push(captures[1].to_string());
+                    rest = rest[captures[0].len()..].trim_start();
+                    italics = true;
+                    continue;
+                }
+            } else if let Some(captures) = PLAIN_TAG.captures(rest).filter(|_| !italics) {
+                grammar_tags.push(captures[1].to_string());
+                rest = rest[captures[0].len()..].trim_start();
+                continue;
+            }
+            break;
+        }
+
+        let (text, examples) = match TRAILING_EXAMPLES.captures(rest) {
+            Some(captures) => (
+                captures[1].trim().to_string(),
+                EXAMPLE
+                    .captures_iter(&captures[2])
+                    .map(|example| example[1].trim().to_string())
+                    .filter(|example| !example.is_empty())
+                    .collect(),
+            ),
+            None => (rest.to_string(), vec![]),
+        };
+
+        Sense {
+            number,
+            grammar_tags,
+            text,
+            examples,
+        }
+    }
+
+    /// Render as one line of Telegram HTML
+    pub fn to_html(&self) -> String {
+        let mut html = format!("<b>{}.</b>", self.number);
+        for tag in &self.grammar_tags {
+            html += &format!(" <i>{}</i>", tag);
+        }
+        if !self.text.is_empty() {
+            html += &format!(" {}", self.text);
+        }
+        for example in &self.examples {
+            html += &format!(" <i>{}</i>", example);
+        }
+
+        html
+    }
+}
+
+impl Locution {
+    /// Render as Telegram HTML, the name of the locution and its senses
+    pub fn to_html(&self) -> String {
+        let mut html = format!("<b>{}</b>\n", self.lemma);
+        for sense in &self.senses {
+            html += &format!("{}\n", sense.to_html());
+        }
+
+        html
+    }
+}
+
+impl Entry {
+    /// Parse the content of the `definition` column. Returns `None` if it has
+    /// no numbered senses, definitions from other dictionaries might not
+    /// follow the layout of the DLE.
+    pub fn parse(definition: &str) -> Option<Self> {
+        let mut lines = definition
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty());
+
+        let lemma = strip_tags(lines.next()?);
+        let mut etymology: Option<String> = None;
+        let mut senses: Vec<Sense> = vec![];
+        let mut locutions: Vec<Locution> = vec![];
+        // Lines after the last sense that are not senses themselves
+        let mut pending: Vec<&str> = vec![];
+
+        for line in lines {
+            let Some(captures) = SENSE_LINE.captures(line) else {
+                if senses.is_empty() && locutions.is_empty() {
+                    etymology = Some(match etymology {
+                        Some(etymology) => format!("{}\n{}", etymology, line),
+                        None => line.to_string(),
+                    });
+                } else {
+                    pending.push(line);
+                }
+                continue;
+            };
+
+            let number: u32 = captures[1].parse().ok()?;
+            let sense = Sense::parse(number, &captures[2]);
+            let has_senses = !senses.is_empty() || !locutions.is_empty();
+
+            if number == 1 && has_senses && !pending.is_empty() {
+                // The numbering restarts after the name of a locution
+                let name = pending.pop().unwrap_or_default();
+                let current = match locutions.last_mut() {
+                    Some(locution) => &mut locution.senses,
+                    None => &mut senses,
+                };
+                append_to_last(current, &pending);
+                locutions.push(Locution {
+                    lemma: strip_tags(name),
+                    senses: vec![sense],
+                });
+            } else {
+                let current = match locutions.last_mut() {
+                    Some(locution) => &mut locution.senses,
+                    None => &mut senses,
+                };
+                append_to_last(current, &pending);
+                current.push(sense);
+            }
+
+            pending.clear();
+        }
+
+        let current = match locutions.last_mut() {
+            Some(locution) => &mut locution.senses,
+            None => &mut senses,
+        };
+        append_to_last(current, &pending);
+
+        if senses.is_empty() && locutions.is_empty() {
+            return None;
+        }
+
+        Some(Entry {
+            lemma,
+            etymology,
+            senses,
+            locutions,
+        })
+    }
+
+    /// Locution of the entry written as `text`, ignoring case and accents
+    pub fn find_locution(&self, text: &str) -> Option<&Locution> {
+        let text = unaccent(text.trim());
+        self.locutions
+            .iter()
+            .find(|locution| unaccent(&locution.lemma) == text)
+    }
+
+    /// Every sense of the entry in order, those of the locutions along with
+    /// the name of their locution
+    pub fn all_senses(&self) -> Vec<(Option<&str>, &Sense)> {
+        self.senses
+            .iter()
+            .map(|sense| (None, sense))
+            .chain(self.locutions.iter().flat_map(|locution| {
+                locution
+                    .senses
+                    .iter()
+                    .map(|sense| (Some(locution.lemma.as_str()), sense))
+            }))
+            .collect()
+    }
+}
+
+/// `definition` with its sense lines rendered from their `Sense`, so every
+/// sense reaches the user with the same layout. The header, the etymology
+/// and the lines appended to a sense (synonyms, references, usage notes) are
+/// kept as they are, and so are definitions that can't be parsed.
+pub fn render_definition(definition: &str) -> String {
+    if Entry::parse(definition).is_none() {
+        return definition.to_string();
+    }
+
+    let mut header_seen = false;
+    definition
+        .split('\n')
+        .map(|line| {
+            let trimmed = line.trim();
+            if trimmed.is_empty() || !std::mem::replace(&mut header_seen, true) {
+                return line.to_string();
+            }
+
+            SENSE_LINE
+                .captures(trimmed)
+                .and_then(|captures| {
+                    let number: u32 = captures[1].parse().ok()?;
+                    Some(Sense::parse(number, &captures[2]).to_html())
+                })
+                .unwrap_or_else(|| line.to_string())
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+fn append_to_last(senses: &mut [Sense], lines: &[&str]) {
+    if let Some(sense) = senses.last_mut() {
+        for line in lines {
+            sense.text += &format!("\n{}", line);
+        }
+    }
+}
+
+#[test]
+fn test_parse_entry() {
+    let definition = "<b>casa</b>\n\nDel lat. casa 'choza'.\n\n<b>1.</b> <i>f.</i> Edificio para habitar. <i>Una casa de ocho plantas.</i>\n<b>2.</b> <i>f.</i> Edificio, piso o apartamento destinado a vivienda.\nSin.: vivienda, hogar.\n<b>3.</b> <i>f.</i> Familia de una casa. <i>Toda la casa lo sabía.</i> <i>Es amigo de la casa.</i>\n\n<b>casa de socorro</b>\n<b>1.</b> <i>f.</i> Establecimiento sanitario donde se prestan los primeros auxilios.\n\n<b>echar la casa por la ventana</b>\n<b>1.</b> <i>loc. verb.</i> <i>coloq.</i> Gastar con esplendidez.\n";
+
+    let entry = Entry::parse(definition).unwrap();
+
+    assert_eq!(entry.lemma, "casa");
+    assert_eq!(entry.etymology.as_deref(), Some("Del lat. casa 'choza'."));
+    assert_eq!(entry.senses.len(), 3);
+    assert_eq!(
+        entry.senses[0],
+        Sense {
+            number: 1,
+            grammar_tags: vec!["f.".to_string()],
+            text: "Edificio para habitar.".to_string(),
+            examples: vec!["Una casa de ocho plantas.".to_string()],
+        }
+    );
+    assert_eq!(
+        entry.senses[1].text,
+        "Edificio, piso o apartamento destinado a vivienda.\nSin.: vivienda, hogar."
+    );
+    assert_eq!(
+        entry.senses[2].examples,
+        ["Toda la casa lo sabía.", "Es amigo de la casa."]
+    );
+    assert_eq!(entry.locutions.len(), 2);
+    assert_eq!(entry.locutions[0].lemma, "casa de socorro");
+    assert_eq!(entry.locutions[1].lemma, "echar la casa por la ventana");
+    assert_eq!(
+        entry.find_locution("Casa de socorro"),
+        Some(&entry.locutions[0])
+    );
+    assert_eq!(
+        entry.locutions[1].senses[0].grammar_tags,
+        ["loc. verb.", "coloq."]
+    );
+    assert_eq!(entry.locutions[1].senses[0].text, "Gastar con esplendidez.");
+    assert!(render_definition(definition).contains(
+        "\n<b>1.</b> <i>f.</i> Edificio para habitar. <i>Una casa de ocho plantas.</i>\n"
+    ));
+}
+
+#[test]
+fn test_parse_entry_without_etymology() {
+    let definition = "niño, ña\n1. adj. Que está en la niñez. U. t. c. s.\n2. m. y f. Persona que tiene pocos años.";
+
+    let entry = Entry::parse(definition).unwrap();
+
+    assert_eq!(entry.lemma, "niño, ña");
+    assert_eq!(entry.etymology, None);
+    assert_eq!(entry.senses[0].grammar_tags, ["adj."]);
+    assert_eq!(entry.senses[0].text, "Que está en la niñez. U. t. c. s.");
+    assert_eq!(entry.senses[1].grammar_tags, ["m. y f."]);
+    assert_eq!(entry.senses[1].text, "Persona que tiene pocos años.");
+    assert!(entry.locutions.is_empty());
+}
+
+#[test]
+fn test_parse_entry_without_senses() {
+    assert_eq!(Entry::parse(""), None);
+    assert_eq!(
+        Entry::parse("<b>solo</b>\n\nEste adverbio puede escribirse sin tilde."),
+        None
+    );
+}
+
+#[test]
+fn test_parse_dle_entry_with_locutions() {
+    // Start of the article «bote¹» of the DLE as stored in the database
+    let definition = "<b>bote¹</b>\n\nDe <i>botar</i>.\n\n<b>1.</b> <i>m.</i> Salto que da la pelota al chocar con una superficie dura.\n<b>2.</b> <i>m.</i> Salto que da una persona o un animal.\nSin.: salto, brinco.\n<b>3.</b> <i>m.</i> Golpe dado con la pica, lanza u otra arma semejante.\n\n<b>a bote pronto</b>\n<b>1.</b> <i>loc. adv.</i> <i>Dep.</i> Dicho de golpear un balón: Justamente cuando bota. U. t. c. loc. adj.\n<b>2.</b> <i>loc. adv.</i> De improviso, sin tiempo para pensar. U. t. c. loc. adj.\n\n<b>de bote en bote</b>\n<b>1.</b> <i>loc. adj.</i> <i>coloq.</i> Dicho de un lugar: Completamente lleno de gente. U. t. c. loc. adv.\n";
+
+    let entry = Entry::parse(definition).unwrap();
+
+    assert_eq!(entry.lemma, "bote¹");
+    assert_eq!(entry.etymology.as_deref(), Some("De <i>botar</i>."));
+    assert_eq!(entry.senses.len(), 3);
+    assert_eq!(
+        entry.senses[1].text,
+        "Salto que da una persona o un animal.\nSin.: salto, brinco."
+    );
+    assert_eq!(entry.locutions.len(), 2);
+    assert_eq!(
+        entry
+            .find_locution("a bote pronto")
+            .map(|locution| locution.senses.len()),
+        Some(2)
+    );
+    assert_eq!(
+        entry.locutions[0].senses[0],
+        Sense {
+            number: 1,
+            grammar_tags: vec!["loc. adv.".to_string(), "Dep.".to_string()],
+            text: "Dicho de golpear un balón: Justamente cuando bota. U. t. c. loc. adj."
+                .to_string(),
+            examples: vec![],
+        }
+    );
+    assert_eq!(entry.locutions[1].lemma, "de bote en bote");
+    assert_eq!(
+        entry.locutions[1].senses[0].grammar_tags,
+        ["loc. adj.", "coloq."]
+    );
+    assert_eq!(entry.all_senses().len(), 6);
+}
+
+#[test]
+fn test_parse_dle_entry_with_examples() {
+    // Start of the article «casa» of the DLE as stored in the database
+    let definition = "<b>casa</b>\n\nDel lat. <i>casa</i> 'choza'.\n\n<b>1.</b> <i>f.</i> Edificio para habitar. <i>Una casa de ocho plantas.</i>\nSin.: vivienda, hogar, domicilio, residencia, morada.\n<b>2.</b> <i>f.</i> Edificio, piso o apartamento destinado a vivienda.\n<b>3.</b> <i>f.</i> Familia de una casa. <i>Está emparentado con la casa de Fernández.</i>\n<b>4.</b> <i>f.</i> Conjunto de los asuntos domésticos. <i>Ocuparse de la casa.</i>\n";
+
+    let entry = Entry::parse(definition).unwrap();
+
+    assert_eq!(entry.lemma, "casa");
+    assert_eq!(
+        entry.etymology.as_deref(),
+        Some("Del lat. <i>casa</i> 'choza'.")
+    );
+    assert_eq!(entry.senses.len(), 4);
+    // The synonyms after the example are kept in the text of the sense
+    assert_eq!(
+        entry.senses[0].text,
+        "Edificio para habitar.\nSin.: vivienda, hogar, domicilio, residencia, morada."
+    );
+    assert_eq!(entry.senses[0].examples, ["Una casa de ocho plantas."]);
+    assert_eq!(
+        entry.senses[2].examples,
+        ["Está emparentado con la casa de Fernández."]
+    );
+    assert_eq!(entry.senses[3].text, "Conjunto de los asuntos domésticos.");
+    assert_eq!(entry.senses[3].examples, ["Ocuparse de la casa."]);
+    assert!(entry.locutions.is_empty());
+}
+
+#[test]
+fn test_render_definition_keeps_header_and_appended_lines() {
+    let definition = "<b>casa</b>\n\nDel lat. <i>casa</i> 'choza'.\n\n<b>1.</b>  <i>f.</i> Edificio para habitar. <i>Una casa de ocho plantas.</i>\nSin.: vivienda, hogar, domicilio.\n2. f. Familia de una casa. <i>Toda la casa lo sabía.</i>\n";
+
+    assert_eq!(
+        render_definition(definition),
+        "<b>casa</b>\n\nDel lat. <i>casa</i> 'choza'.\n\n<b>1.</b> <i>f.</i> Edificio para habitar. <i>Una casa de ocho plantas.</i>\nSin.: vivienda, hogar, domicilio.\n<b>2.</b> <i>f.</i> Familia de una casa. <i>Toda la casa lo sabía.</i>\n"
+    );
+    assert_eq!(
+        render_definition("<b>solo</b>\n\nEste adverbio puede escribirse sin tilde."),
+        "<b>solo</b>\n\nEste adverbio puede escribirse sin tilde."
+    );
+}
+
+#[test]
+fn test_parse_one_word_senses() {
+    assert_eq!(
+        Sense::parse(1, "<i>m.</i> Salto."),
+        Sense {
+            number: 1,
+            grammar_tags: vec!["m.".to_string()],
+            text: "Salto.".to_string(),
+            examples: vec![],
+        }
+    );
+    assert_eq!(
+        Sense::parse(2, "<i>m.</i> Golpe. Dado con fuerza.").text,
+        "Golpe. Dado con fuerza."
+    );
+    assert_eq!(Sense::parse(3, "m. y f. Salto.").grammar_tags, ["m. y f."]);
+    assert_eq!(Sense::parse(3, "m. y f. Salto.").text, "Salto.");
+    assert_eq!(
+        render_definition("<b>bote¹</b>\n\n<b>1.</b> <i>m.</i> Salto.\n"),
+        "<b>bote¹</b>\n\n<b>1.</b> <i>m.</i> Salto.\n"
+    );
+}
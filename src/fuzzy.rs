@@ -0,0 +1,46 @@
+use crate::{phonetic::phonetic_key, utils::unaccent};
+
+const SIMILARITY_WEIGHT: f32 = 0.4;
+const DISTANCE_WEIGHT: f32 = 0.3;
+const PREFIX_WEIGHT: f32 = 0.1;
+const PHONETIC_WEIGHT: f32 = 0.2;
+
+/// Score of `lemma` as a suggestion for the misspelled `word`, between 0 and 1.
+///
+/// # Arguments
+///
+/// * `word` - The word that was not found
+/// * `lemma` - The suggested lemma
+/// * `similarity` - Trigram similarity between both, as given by `pg_trgm`
+/// * `distance` - Levenshtein distance between both
+///
+pub fn score(word: &str, lemma: &str, similarity: f32, distance: i32) -> f32 {
+    let word = unaccent(word);
+    let lemma = unaccent(lemma);
+
+    let shared_prefix = word
+        .chars()
+        .zip(lemma.chars())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let prefix = shared_prefix as f32 / word.chars().count().max(1) as f32;
+
+    let phonetic = if phonetic_key(&word) == phonetic_key(&lemma) {
+        1.0
+    } else {
+        0.0
+    };
+
+    SIMILARITY_WEIGHT * similarity
+        + DISTANCE_WEIGHT / (1.0 + distance.max(0) as f32)
+        + PREFIX_WEIGHT * prefix
+        + PHONETIC_WEIGHT * phonetic
+}
+
+#[test]
+fn test_score() {
+    // Same trigram similarity and distance, the homophone wins
+    assert!(score("vaca", "baca", 0.3, 1) > score("vaca", "taca", 0.3, 1));
+    // Sharing the beginning is better than the end
+    assert!(score("caza", "cazo", 0.3, 1) > score("caza", "raza", 0.3, 1));
+}
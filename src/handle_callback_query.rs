@@ -1,11 +1,24 @@
 use teloxide::{
     prelude::*,
-    types::{InlineKeyboardButton, InlineKeyboardMarkup, MaybeInaccessibleMessage, User},
+    types::{MaybeInaccessibleMessage, Me},
 };
 
 use crate::{
+    abbreviation::send_abbreviations_of,
+    chat_settings::toggle_chat_setting,
+    compact::{show_full_definition, show_sense},
+    conjugation::send_conjugation,
     database::DatabaseHandler,
-    utils::{DESUBS_CALLBACK_DATA, SUBS_CALLBACK_DATA},
+    handle_message::{send_definition, send_message},
+    pattern::show_pattern_page,
+    relation::send_relations,
+    settings::{edit_settings, handle_settings_callback, SUBSCRIPTION},
+    utils::{
+        ABBREVIATIONS_CALLBACK_PREFIX, CHAT_SETTINGS_CALLBACK_PREFIX, CONJUGATION_CALLBACK_PREFIX,
+        DESUBS_CALLBACK_DATA, FULL_DEFINITION_CALLBACK_PREFIX, PATTERN_CALLBACK_PREFIX,
+        RELATIONS_CALLBACK_PREFIX, SENSE_CALLBACK_PREFIX, SETTINGS_CALLBACK_PREFIX,
+        SOURCE_CALLBACK_PREFIX, SUBS_CALLBACK_DATA, WORD_PICK_CALLBACK_PREFIX,
+    },
     DLEBot,
 };
 
@@ -13,74 +26,190 @@ pub async fn handle_callback_query(
     db_handler: DatabaseHandler,
     bot: DLEBot,
     query: CallbackQuery,
+    me: Me,
 ) -> ResponseResult<()> {
     if let Ok(user_id) = query.from.id.0.try_into() {
-        async fn edit_message(
-            subscribed: bool,
-            message: Message,
-            user: User,
-            bot: DLEBot,
-        ) -> ResponseResult<()> {
-            let inline_keyboard = InlineKeyboardMarkup::new([[InlineKeyboardButton::callback(
-                if subscribed {
-                    "Desuscribirme"
-                } else {
-                    "¡Suscribirme!"
-                },
-                if subscribed {
-                    DESUBS_CALLBACK_DATA
-                } else {
-                    SUBS_CALLBACK_DATA
-                },
-            )]]);
-
-            let chat_id = message.chat.id;
-            let message_id = message.id;
-
-            bot.clone()
-                .edit_message_text(
-                    chat_id,
-                    message_id,
-                    format!(
-                        include_str!("templates/subscription.txt"),
-                        user.first_name,
-                        if subscribed { "SÍ" } else { "NO" }
-                    ),
-                )
-                .await?;
-
-            bot.edit_message_reply_markup(chat_id, message_id)
-                .reply_markup(inline_keyboard)
-                .await?;
-
-            Ok(())
-        }
-
         match query.data.as_deref() {
             Some(SUBS_CALLBACK_DATA) => {
-                bot.answer_callback_query(query.id.clone())
+                bot.answer_callback_query(&query.id)
                     .text("✅ ¡Te has suscrito!")
                     .await?;
-                if let Some(MaybeInaccessibleMessage::Regular(message)) = query.message {
-                    edit_message(true, message, query.from, bot).await?;
-                }
                 db_handler.set_subscribed(user_id, true).await;
+                edit_settings(&db_handler, &bot, &query, user_id, SUBSCRIPTION).await?;
                 db_handler
                     .add_callback_query_event(user_id, SUBS_CALLBACK_DATA.to_string())
                     .await;
             }
             Some(DESUBS_CALLBACK_DATA) => {
-                bot.answer_callback_query(query.id)
+                bot.answer_callback_query(&query.id)
                     .text("❌ Te has desuscrito.")
                     .await?;
-                if let Some(MaybeInaccessibleMessage::Regular(message)) = query.message {
-                    edit_message(false, message, query.from, bot).await?;
-                }
                 db_handler.set_subscribed(user_id, false).await;
+                edit_settings(&db_handler, &bot, &query, user_id, SUBSCRIPTION).await?;
                 db_handler
                     .add_callback_query_event(user_id, DESUBS_CALLBACK_DATA.to_string())
                     .await;
             }
+            Some(data) if data.starts_with(SETTINGS_CALLBACK_PREFIX) => {
+                bot.answer_callback_query(&query.id).await?;
+                handle_settings_callback(
+                    &db_handler,
+                    &bot,
+                    &query,
+                    user_id,
+                    &data[SETTINGS_CALLBACK_PREFIX.len()..],
+                )
+                .await?;
+                db_handler
+                    .add_callback_query_event(user_id, data.to_string())
+                    .await;
+            }
+            Some(data) if data.starts_with(CONJUGATION_CALLBACK_PREFIX) => {
+                bot.answer_callback_query(&query.id).await?;
+                if let Some(message) = &query.message {
+                    let verb = &data[CONJUGATION_CALLBACK_PREFIX.len()..];
+                    send_conjugation(db_handler.clone(), bot, message.chat().id, verb).await?;
+                }
+                db_handler
+                    .add_callback_query_event(user_id, data.to_string())
+                    .await;
+            }
+            Some(data) if data.starts_with(SOURCE_CALLBACK_PREFIX) => {
+                bot.answer_callback_query(&query.id).await?;
+                if let Some((source, lemma)) = data[SOURCE_CALLBACK_PREFIX.len()..].split_once(':')
+                {
+                    if let (Some(message), Some(word)) =
+                        (&query.message, db_handler.get_exact_in(lemma, source).await)
+                    {
+                        send_definition(
+                            &db_handler,
+                            &bot,
+                            message.chat().id,
+                            user_id,
+                            chrono::Utc::now(),
+                            word,
+                            &me,
+                        )
+                        .await?;
+                    }
+                }
+                db_handler
+                    .add_callback_query_event(user_id, data.to_string())
+                    .await;
+            }
+            Some(data) if data.starts_with(ABBREVIATIONS_CALLBACK_PREFIX) => {
+                bot.answer_callback_query(&query.id).await?;
+                if let Some((source, lemma)) =
+                    data[ABBREVIATIONS_CALLBACK_PREFIX.len()..].split_once(':')
+                {
+                    if let (Some(message), Some(word)) =
+                        (&query.message, db_handler.get_exact_in(lemma, source).await)
+                    {
+                        send_abbreviations_of(&db_handler, &bot, message.chat().id, &word).await?;
+                    }
+                }
+                db_handler
+                    .add_callback_query_event(user_id, data.to_string())
+                    .await;
+            }
+            Some(data) if data.starts_with(RELATIONS_CALLBACK_PREFIX) => {
+                bot.answer_callback_query(&query.id).await?;
+                if let Some(message) = &query.message {
+                    let lemma = &data[RELATIONS_CALLBACK_PREFIX.len()..];
+                    send_relations(&db_handler, &bot, message.chat().id, lemma, &me).await?;
+                }
+                db_handler
+                    .add_callback_query_event(user_id, data.to_string())
+                    .await;
+            }
+            Some(data) if data.starts_with(PATTERN_CALLBACK_PREFIX) => {
+                bot.answer_callback_query(&query.id).await?;
+                if let Some((offset, pattern)) = data[PATTERN_CALLBACK_PREFIX.len()..]
+                    .split_once(':')
+                    .and_then(|(offset, pattern)| Some((offset.parse::<u64>().ok()?, pattern)))
+                {
+                    if let Some(message) = &query.message {
+                        show_pattern_page(
+                            &db_handler,
+                            &bot,
+                            message.chat().id,
+                            message.id(),
+                            pattern,
+                            offset,
+                            &me,
+                        )
+                        .await?;
+                    }
+                }
+                db_handler
+                    .add_callback_query_event(user_id, data.to_string())
+                    .await;
+            }
+            Some(data) if data.starts_with(SENSE_CALLBACK_PREFIX) => {
+                bot.answer_callback_query(&query.id).await?;
+                if let Some((index, source, lemma)) = data[SENSE_CALLBACK_PREFIX.len()..]
+                    .split_once(':')
+                    .and_then(|(index, rest)| {
+                        let (source, lemma) = rest.split_once(':')?;
+                        Some((index.parse::<usize>().ok()?, source, lemma))
+                    })
+                {
+                    if let (Some(message), Some(word)) =
+                        (&query.message, db_handler.get_exact_in(lemma, source).await)
+                    {
+                        show_sense(&db_handler, &bot, message, word, user_id, index, &me).await?;
+                    }
+                }
+                db_handler
+                    .add_callback_query_event(user_id, data.to_string())
+                    .await;
+            }
+            Some(data) if data.starts_with(FULL_DEFINITION_CALLBACK_PREFIX) => {
+                bot.answer_callback_query(&query.id).await?;
+                if let Some((source, lemma)) =
+                    data[FULL_DEFINITION_CALLBACK_PREFIX.len()..].split_once(':')
+                {
+                    if let (Some(message), Some(word)) =
+                        (&query.message, db_handler.get_exact_in(lemma, source).await)
+                    {
+                        show_full_definition(&db_handler, &bot, message, word, user_id, &me)
+                            .await?;
+                    }
+                }
+                db_handler
+                    .add_callback_query_event(user_id, data.to_string())
+                    .await;
+            }
+            Some(data) if data.starts_with(WORD_PICK_CALLBACK_PREFIX) => {
+                bot.answer_callback_query(&query.id).await?;
+                if let Some(MaybeInaccessibleMessage::Regular(message)) = &query.message {
+                    let word = &data[WORD_PICK_CALLBACK_PREFIX.len()..];
+                    send_message(db_handler.clone(), bot, message.clone(), user_id, word, me)
+                        .await?;
+                }
+                db_handler
+                    .add_callback_query_event(user_id, data.to_string())
+                    .await;
+            }
+            Some(data) if data.starts_with(CHAT_SETTINGS_CALLBACK_PREFIX) => {
+                if let Some(message) = &query.message {
+                    let setting = &data[CHAT_SETTINGS_CALLBACK_PREFIX.len()..];
+                    toggle_chat_setting(
+                        &db_handler,
+                        &bot,
+                        &query.id,
+                        &query.from,
+                        message,
+                        setting,
+                    )
+                    .await?;
+                } else {
+                    bot.answer_callback_query(&query.id).await?;
+                }
+                db_handler
+                    .add_callback_query_event(user_id, data.to_string())
+                    .await;
+            }
             _ => {
                 bot.answer_callback_query(&query.id).await?;
                 log::warn!("Unrecognized callback query: {:?}", query);
@@ -1,6 +1,6 @@
 use teloxide::prelude::*;
 
-use crate::database::DatabaseHandler;
+use crate::{chat_settings::is_group, database::DatabaseHandler};
 
 pub async fn handle_my_chat_member(
     db_handler: DatabaseHandler,
@@ -12,6 +12,18 @@ pub async fn handle_my_chat_member(
         new_chat_member,
         ..
     } = &update;
+
+    if is_group(&update.chat) {
+        if old_chat_member.is_present() && !new_chat_member.is_present() {
+            db_handler.remove_chat(update.chat.id.0).await;
+        } else if !old_chat_member.is_present() && new_chat_member.is_present() {
+            db_handler
+                .set_chat(db_handler.get_chat(update.chat.id.0).await)
+                .await;
+        }
+        return Ok(());
+    }
+
     if let Ok(user_id) = from.id.0.try_into() {
         if old_chat_member.is_present() && !new_chat_member.is_present() {
             db_handler.set_in_bot(user_id, false).await;
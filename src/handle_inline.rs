@@ -1,5 +1,5 @@
 use teloxide::{
-    payloads::AnswerInlineQuery,
+    payloads::{AnswerInlineQuery, AnswerInlineQuerySetters},
     prelude::*,
     types::{
         InlineQueryResult, InlineQueryResultArticle, InlineQueryResultsButton,
@@ -8,11 +8,174 @@ use teloxide::{
 };
 
 use crate::{
-    database::DatabaseHandler,
-    utils::{base64_encode, smart_split, DISABLED_LINK_PREVIEW, MAX_MASSAGE_LENGTH},
+    cross_reference::{link_references, references},
+    database::{DatabaseHandler, DictionaryModel, DleModel, RelatedLemma, DEFAULT_DICTIONARY},
+    entry::{render_definition, Entry},
+    handle_message::with_source_badge,
+    pattern::Pattern,
+    relation::kind_title,
+    utils::{base64_encode, lemma_link, smart_split, DISABLED_LINK_PREVIEW, MAX_MASSAGE_LENGTH},
     DLEBot,
 };
 
+/// Queries starting with this prefix search definitions instead of lemmas
+const REVERSE_SEARCH_PREFIX: &str = "?";
+const INLINE_REVERSE_SEARCH_LIMIT: u64 = 10;
+/// Queries starting with this prefix list the synonyms, antonyms and related
+/// words of a lemma
+const RELATION_PREFIX: &str = "=";
+/// Queries starting with this prefix search lemmas with a pattern, see
+/// `Pattern::parse`
+const PATTERN_PREFIX: &str = "#";
+/// Number of similar lemmas suggested when nothing starts with the query
+const INLINE_FUZZY_SUGGESTIONS: usize = 5;
+/// Number of locutions shown before the lemmas in the first page of results
+const INLINE_LOCUTIONS: u64 = 5;
+/// Ids of the results with a locution start with this prefix
+const LOCUTION_RESULT_PREFIX: &str = "~";
+/// Maximum size in bytes of the id of an inline result
+const MAX_RESULT_ID_LENGTH: usize = 64;
+/// Maximum number of results Telegram accepts in one answer
+const MAX_INLINE_RESULTS: usize = 50;
+/// Number of entries fetched for each page of results
+const INLINE_PAGE_SIZE: u64 = 50;
+/// Larger offsets, which no page of results reaches, are read as the first
+/// page so they can't overflow the parameters of the queries
+const MAX_INLINE_OFFSET: u64 = 1_000_000;
+
+/// One article per part of the definition of `word`, with the references to
+/// the lemmas in `references` linked
+fn definition_articles(
+    word: &DleModel,
+    dictionaries: &[DictionaryModel],
+    references: &[String],
+    me: &Me,
+) -> Vec<InlineQueryResult> {
+    let dictionary = dictionaries
+        .iter()
+        .find(|dictionary| dictionary.code == word.source);
+    let definition = link_references(
+        &render_definition(&word.definition),
+        references,
+        me.username(),
+    );
+    let definition = with_source_badge(&definition, word, dictionaries);
+    // Results from the default dictionary keep the ids they had before
+    // other dictionaries were added
+    let (result_prefix, title) = match dictionary {
+        Some(dictionary) if dictionary.code != DEFAULT_DICTIONARY => (
+            format!("{}:", dictionary.code),
+            format!("{} ({})", word.lemma, dictionary.short_name),
+        ),
+        _ => (String::new(), word.lemma.clone()),
+    };
+
+    let mut articles: Vec<InlineQueryResult> = vec![];
+
+    for (id, &part) in smart_split(&definition, MAX_MASSAGE_LENGTH)
+        .iter()
+        .enumerate()
+    {
+        let part = if id == 0 {
+            part.to_string()
+        } else {
+            format!("{}\n{}", &word.lemma, part)
+        };
+
+        let part_with_deep_link =
+            part.replacen(&word.lemma, &lemma_link(me.username(), &word.lemma), 1);
+
+        articles.push(InlineQueryResult::Article(
+            InlineQueryResultArticle::new(
+                format!("{}{}_{}", result_prefix, &word.lemma, id),
+                &title,
+                InputMessageContent::Text(
+                    InputMessageContentText::new(part_with_deep_link)
+                        .link_preview_options(DISABLED_LINK_PREVIEW)
+                        .parse_mode(ParseMode::Html),
+                ),
+            )
+            .description(part),
+        ));
+    }
+
+    articles
+}
+
+/// Article with the locution `locution` as defined in the entry of `word`
+fn locution_article(
+    word: &DleModel,
+    locution: &str,
+    dictionaries: &[DictionaryModel],
+    me: &Me,
+) -> Option<InlineQueryResult> {
+    let entry = Entry::parse(&word.definition)?;
+    let index = entry
+        .locutions
+        .iter()
+        .position(|candidate| candidate.lemma == locution)?;
+    let id = format!(
+        "{}{}:{}_{}",
+        LOCUTION_RESULT_PREFIX, word.source, word.lemma, index
+    );
+    if id.len() > MAX_RESULT_ID_LENGTH {
+        return None;
+    }
+
+    let text = with_source_badge(
+        &format!(
+            "{}\n<i>En la entrada</i> {}",
+            entry.locutions[index].to_html(),
+            lemma_link(me.username(), &word.lemma)
+        ),
+        word,
+        dictionaries,
+    );
+    let text = smart_split(&text, MAX_MASSAGE_LENGTH)[0].to_string();
+
+    Some(InlineQueryResult::Article(
+        InlineQueryResultArticle::new(
+            id,
+            locution,
+            InputMessageContent::Text(
+                InputMessageContentText::new(text)
+                    .link_preview_options(DISABLED_LINK_PREVIEW)
+                    .parse_mode(ParseMode::Html),
+            ),
+        )
+        .description(format!("Locución de «{}»", word.lemma)),
+    ))
+}
+
+/// One article per lemma related to `lemma`, linked to its definition
+fn relation_articles(lemma: &str, relations: &[RelatedLemma], me: &Me) -> Vec<InlineQueryResult> {
+    relations
+        .iter()
+        .map(|relation| (relation, format!("{}{}", RELATION_PREFIX, relation.related)))
+        .filter(|(_, id)| id.len() <= MAX_RESULT_ID_LENGTH)
+        .take(MAX_INLINE_RESULTS)
+        .map(|(relation, id)| {
+            let description = format!("{} de «{}»", kind_title(&relation.kind), lemma);
+            InlineQueryResult::Article(
+                InlineQueryResultArticle::new(
+                    id,
+                    &relation.related,
+                    InputMessageContent::Text(
+                        InputMessageContentText::new(format!(
+                            "{}\n<i>{}</i>",
+                            lemma_link(me.username(), &relation.related),
+                            description
+                        ))
+                        .link_preview_options(DISABLED_LINK_PREVIEW)
+                        .parse_mode(ParseMode::Html),
+                    ),
+                )
+                .description(description),
+            )
+        })
+        .collect()
+}
+
 pub async fn handle_inline(
     db_handler: DatabaseHandler,
     bot: DLEBot,
@@ -23,48 +186,138 @@ pub async fn handle_inline(
         return Ok(());
     }
 
-    let words = db_handler.get_list_like(&q.query).await;
+    let dictionaries = db_handler.get_dictionaries().await;
+    let offset: u64 = q
+        .offset
+        .parse()
+        .ok()
+        .filter(|offset| *offset <= MAX_INLINE_OFFSET)
+        .unwrap_or(0);
+
+    // A query like `dpd:palabra` only searches in the dictionary `dpd`
+    let source = q.query.split_once(':').and_then(|(code, query)| {
+        dictionaries
+            .iter()
+            .find(|dictionary| dictionary.code == code.trim().to_lowercase())
+            .map(|dictionary| (dictionary.code.as_str(), query.trim()))
+    });
+
+    // Dictionary chosen in /ajustes for queries that don't name one
+    let inline_dictionary = match i64::try_from(q.from.id.0) {
+        Ok(user_id) => db_handler
+            .get_inline_dictionary(user_id)
+            .await
+            .filter(|code| {
+                dictionaries
+                    .iter()
+                    .any(|dictionary| &dictionary.code == code)
+            }),
+        Err(_) => None,
+    };
 
     let mut results: Vec<InlineQueryResult> = vec![];
+    let mut consumed: u64 = 0;
 
-    for word in words {
-        for (id, &part) in smart_split(&word.definition, MAX_MASSAGE_LENGTH)
-            .iter()
-            .enumerate()
-        {
-            let part = if id == 0 {
-                part.to_string()
-            } else {
-                format!("{}\n{}", &word.lemma, part)
-            };
-
-            let part_with_deep_link = part.replacen(
-                &word.lemma,
-                &format!(
-                    r#"<a href="https://t.me/{}?start={}">{}</a>"#,
-                    me.username(),
-                    base64_encode(word.lemma.clone()),
-                    word.lemma
-                ),
-                1,
-            );
+    let (words, paginated) = if let Some(lemma) = q.query.strip_prefix(RELATION_PREFIX) {
+        if offset == 0 {
+            let lemma = lemma.trim();
+            let relations = db_handler.get_relations(lemma).await;
+            results.extend(relation_articles(lemma, &relations, &me));
+        }
+        (vec![], false)
+    } else if let Some(query) = q.query.strip_prefix(PATTERN_PREFIX) {
+        let words = match Pattern::parse(query) {
+            Some(pattern) => {
+                db_handler
+                    .get_pattern_matches(&pattern, offset, INLINE_PAGE_SIZE)
+                    .await
+            }
+            None => vec![],
+        };
+        (words, true)
+    } else if let Some(description) = q.query.strip_prefix(REVERSE_SEARCH_PREFIX) {
+        let words = if offset == 0 {
+            db_handler
+                .search_definitions(description.trim(), INLINE_REVERSE_SEARCH_LIMIT)
+                .await
+        } else {
+            vec![]
+        };
+        (words, false)
+    } else {
+        let (query, source) = match source {
+            Some((code, query)) => (query, Some(code)),
+            None => (q.query.as_str(), inline_dictionary.as_deref()),
+        };
 
-            results.push(InlineQueryResult::Article(
-                InlineQueryResultArticle::new(
-                    format!("{}_{}", &word.lemma, id),
-                    &word.lemma,
-                    InputMessageContent::Text(
-                        InputMessageContentText::new(part_with_deep_link)
-                            .link_preview_options(DISABLED_LINK_PREVIEW)
-                            .parse_mode(ParseMode::Html),
-                    ),
-                )
-                .description(part),
-            ));
+        // Locutions go first, only in the first page
+        if offset == 0 {
+            for locution in db_handler.get_locutions_like(query, INLINE_LOCUTIONS).await {
+                if source.is_some_and(|code| code != locution.source) {
+                    continue;
+                }
+                if let Some(word) = db_handler
+                    .get_exact_in(&locution.lemma, &locution.source)
+                    .await
+                {
+                    results.extend(locution_article(
+                        &word,
+                        &locution.locution,
+                        &dictionaries,
+                        &me,
+                    ));
+                }
+            }
+        }
+
+        let words = db_handler
+            .get_list_like(query, offset, INLINE_PAGE_SIZE, source)
+            .await;
+
+        if words.is_empty() && results.is_empty() && offset == 0 {
+            // Nothing starts with the query, suggest similar lemmas instead
+            let mut suggestions = vec![];
+            for lemma in db_handler
+                .get_fuzzy_list(query, INLINE_FUZZY_SUGGESTIONS)
+                .await
+            {
+                if let Some(word) = db_handler.get_exact(&lemma).await {
+                    suggestions.push(word);
+                }
+            }
+            (suggestions, false)
+        } else {
+            (words, true)
         }
+    };
+
+    // Checked at once for every definition of the page
+    let mut candidates: Vec<String> = words
+        .iter()
+        .flat_map(|word| references(&word.definition))
+        .collect();
+    candidates.sort();
+    candidates.dedup();
+    let references = db_handler.filter_existing_lemmas(&candidates).await;
+
+    for word in &words {
+        let articles = definition_articles(word, &dictionaries, &references, &me);
+        if !results.is_empty() && results.len() + articles.len() > MAX_INLINE_RESULTS {
+            break;
+        }
+        results.extend(articles);
+        consumed += 1;
     }
+    results.truncate(MAX_INLINE_RESULTS);
+
+    let next_offset =
+        if paginated && (consumed < words.len() as u64 || words.len() as u64 == INLINE_PAGE_SIZE) {
+            (offset + consumed).to_string()
+        } else {
+            String::new()
+        };
 
-    if results.is_empty() {
+    if results.is_empty() && offset == 0 {
         <Bot as Requester>::AnswerInlineQuery::new(
             bot.inner().clone(),
             AnswerInlineQuery {
@@ -81,7 +334,10 @@ pub async fn handle_inline(
         )
         .await?;
     } else {
-        bot.answer_inline_query(q.id, results).await?;
+        bot.answer_inline_query(q.id, results)
+            .next_offset(next_offset)
+            .is_personal(inline_dictionary.is_some())
+            .await?;
     }
 
     Ok(())
@@ -1,4 +1,5 @@
-use chrono::NaiveDate;
+use chrono::{DateTime, NaiveDate, Utc};
+use sea_orm::DbErr;
 use teloxide::{
     payloads::SendMessageSetters,
     prelude::*,
@@ -6,14 +7,38 @@ use teloxide::{
         InlineKeyboardButton, InlineKeyboardMarkup, KeyboardButton, KeyboardMarkup, Me,
         ReplyParameters,
     },
-    utils::command::{BotCommands, ParseError},
+    utils::{
+        command::{BotCommands, ParseError},
+        html::escape,
+    },
 };
 
 use crate::{
+    abbreviation::send_abbreviation,
     broadcast::broadcast_for_all,
-    database::DatabaseHandler,
+    chat_settings::{is_group, send_chat_settings},
+    compact::compact_view,
+    conjugation::{send_conjugation, Conjugation},
+    cross_reference::{existing_references, link_references},
+    database::{DatabaseHandler, DictionaryModel, DleModel, OutputFormat, DEFAULT_DICTIONARY},
+    delivery::send_delivery_settings,
+    entry::{render_definition, Entry},
     image::send_image,
-    utils::{base64_decode, base64_encode, smart_split, DISABLED_LINK_PREVIEW, MAX_MASSAGE_LENGTH},
+    pattern::send_pattern_matches,
+    phonetic::homophone_notes,
+    relation::send_relations,
+    rhyme::{send_anagrams, send_rhymes},
+    settings::{send_settings, MENU, SUBSCRIPTION},
+    spelling::send_spelling_check,
+    syllable::{send_syllables, syllables_line},
+    utils::{
+        base64_decode, dictionary_url, lemma_link, smart_split, ABBREVIATIONS_CALLBACK_PREFIX,
+        CONJUGATION_CALLBACK_PREFIX, DISABLED_LINK_PREVIEW, MAX_CALLBACK_DATA_LENGTH,
+        MAX_CAPTION_LENGTH, MAX_MASSAGE_LENGTH, RELATIONS_CALLBACK_PREFIX, SOURCE_CALLBACK_PREFIX,
+    },
+    word_of_the_day::todays_word,
+    word_picker::send_word_picker,
+    wotd_target::{add_target, delete_target, send_targets, set_target},
     DLEBot,
 };
 
@@ -30,6 +55,38 @@ enum Command {
     Aleatorio,
     #[command(description = "Mostrar la «Palabra del día»")]
     Pdd,
+    #[command(description = "Definición de una palabra")]
+    Def(String),
+    #[command(description = "Conjugar un verbo")]
+    Conjugar(String),
+    #[command(description = "Buscar palabras por su significado")]
+    Buscar(String),
+    #[command(description = "Lista de diccionarios disponibles")]
+    Diccionarios,
+    #[command(description = "Ver las definiciones acepción por acepción")]
+    Compacto,
+    #[command(description = "Significado de una abreviatura")]
+    Abreviatura(String),
+    #[command(description = "Sinónimos y antónimos de una palabra")]
+    Sinonimos(String),
+    #[command(description = "Buscar palabras que encajen con un patrón")]
+    Patron(String),
+    #[command(description = "Anagramas de una palabra")]
+    Anagramas(String),
+    #[command(description = "Palabras que riman con otra")]
+    Rima(String),
+    #[command(description = "Dividir una palabra en sílabas")]
+    Silabas(String),
+    #[command(description = "Revisar la ortografía de un texto")]
+    Corregir(String),
+    #[command(description = "Tus ajustes")]
+    Ajustes,
+    #[command(description = "Suscripción a la «Palabra del día»")]
+    Suscripcion,
+    #[command(description = "Hora a la que recibes la «Palabra del día»")]
+    Hora(String),
+    #[command(description = "Ajustes del bot en un grupo")]
+    Configurar,
 }
 
 fn split_by_first_whitespace(text: String) -> Result<(String, String), ParseError> {
@@ -59,6 +116,16 @@ enum AdminCommand {
     SetPdd { date: String, lemma: String },
     #[command(description = "Obtén la lista de palabras programadas")]
     GetSchedule,
+    #[command(description = "Reconstruye los índices derivados del diccionario")]
+    Reindexar,
+    #[command(description = "Lista los canales de la palabra del día")]
+    Targets,
+    #[command(description = "Publica la palabra del día en un canal a una hora")]
+    AddTarget(String),
+    #[command(description = "Cambia la hora, zona, tema, idioma o estado de un canal")]
+    SetTarget(String),
+    #[command(description = "Deja de publicar la palabra del día en un canal")]
+    DelTarget(String),
 }
 
 pub async fn set_commands(bot: DLEBot) -> ResponseResult<()> {
@@ -119,7 +186,7 @@ async fn send_word_of_the_day(
     bot: DLEBot,
     msg: Message,
 ) -> ResponseResult<()> {
-    if let Ok(wotd) = db_handler.get_word_of_the_day().await {
+    if let Some(wotd) = todays_word(&db_handler, &bot).await {
         bot.send_message(
             msg.chat.id,
             format!("📖 Palabra del día\n\n {}", wotd.definition.trim()),
@@ -130,6 +197,450 @@ async fn send_word_of_the_day(
     Ok(())
 }
 
+/// What is shown around a definition besides the definition itself
+pub struct DefinitionContext {
+    /// Dictionaries that define the lemma
+    pub sources: Vec<DictionaryModel>,
+    /// Lemmas referred by the definition that have their own entry
+    pub references: Vec<String>,
+    /// Whether the lemma has synonyms, antonyms or related words
+    pub has_relations: bool,
+    /// Line with the syllables of the lemma, if the user wants to see it
+    pub syllables: Option<String>,
+}
+
+impl DefinitionContext {
+    pub async fn load(db_handler: &DatabaseHandler, word: &DleModel, user_id: i64) -> Self {
+        Self {
+            sources: db_handler.get_sources_of(&word.lemma).await,
+            references: existing_references(db_handler, &word.definition).await,
+            has_relations: db_handler.has_relations(&word.lemma).await,
+            syllables: if db_handler.get_show_syllables(user_id).await {
+                syllables_line(&word.lemma)
+            } else {
+                None
+            },
+        }
+    }
+
+    /// Append the badge of the dictionary of `word` and the syllables of its
+    /// lemma to `text`
+    pub fn with_footer(&self, text: &str, word: &DleModel) -> String {
+        let text = with_source_badge(text, word, &self.sources);
+        match &self.syllables {
+            Some(syllables) => format!("{}\n{}", text.trim_end(), syllables),
+            None => text,
+        }
+    }
+}
+
+/// Inline keyboard with the actions available for a definition
+pub fn definition_keyboard(
+    word: &DleModel,
+    context: &DefinitionContext,
+) -> Option<InlineKeyboardMarkup> {
+    let mut rows: Vec<Vec<InlineKeyboardButton>> = vec![];
+    let mut actions: Vec<InlineKeyboardButton> = vec![];
+
+    let conjugation_data = format!("{}{}", CONJUGATION_CALLBACK_PREFIX, word.lemma);
+    if conjugation_data.len() <= MAX_CALLBACK_DATA_LENGTH && Conjugation::from_word(word).is_some()
+    {
+        actions.push(InlineKeyboardButton::callback(
+            "Conjugación",
+            conjugation_data,
+        ));
+    }
+
+    // Only definitions laid out like the DLE use its abbreviations
+    let abbreviations_data = format!(
+        "{}{}:{}",
+        ABBREVIATIONS_CALLBACK_PREFIX, word.source, word.lemma
+    );
+    if abbreviations_data.len() <= MAX_CALLBACK_DATA_LENGTH
+        && Entry::parse(&word.definition).is_some()
+    {
+        actions.push(InlineKeyboardButton::callback(
+            "Abreviaturas",
+            abbreviations_data,
+        ));
+    }
+
+    let relations_data = format!("{}{}", RELATIONS_CALLBACK_PREFIX, word.lemma);
+    if context.has_relations && relations_data.len() <= MAX_CALLBACK_DATA_LENGTH {
+        actions.push(InlineKeyboardButton::callback("Sinónimos", relations_data));
+    }
+
+    if !actions.is_empty() {
+        rows.push(actions);
+    }
+
+    let sources: Vec<InlineKeyboardButton> = context
+        .sources
+        .iter()
+        .filter(|dictionary| dictionary.code != word.source)
+        .map(|dictionary| {
+            (
+                dictionary,
+                format!(
+                    "{}{}:{}",
+                    SOURCE_CALLBACK_PREFIX, dictionary.code, word.lemma
+                ),
+            )
+        })
+        .filter(|(_, data)| data.len() <= MAX_CALLBACK_DATA_LENGTH)
+        .map(|(dictionary, data)| {
+            InlineKeyboardButton::callback(format!("Ver en {}", dictionary.short_name), data)
+        })
+        .collect();
+    if !sources.is_empty() {
+        rows.push(sources);
+    }
+
+    if rows.is_empty() {
+        None
+    } else {
+        Some(InlineKeyboardMarkup::new(rows))
+    }
+}
+
+/// Line naming the dictionary a definition comes from, linked to its website
+pub fn source_badge(dictionary: &DictionaryModel, lemma: &str) -> String {
+    match dictionary
+        .url
+        .as_ref()
+        .and_then(|url| dictionary_url(url, lemma))
+    {
+        Some(url) => format!(r#"📚 <a href="{}">{}</a>"#, url, dictionary.short_name),
+        None => format!("📚 {}", dictionary.short_name),
+    }
+}
+
+/// Keyboard for the not found message: try inline mode or the website of
+/// the default dictionary
+async fn not_found_keyboard(db_handler: &DatabaseHandler, text: &str) -> InlineKeyboardMarkup {
+    let mut buttons = vec![InlineKeyboardButton::switch_inline_query_current_chat(
+        "Probar inline",
+        "",
+    )];
+
+    if let Some(url) = db_handler
+        .get_dictionary(DEFAULT_DICTIONARY)
+        .await
+        .and_then(|dictionary| dictionary.url)
+    {
+        if let Some(search_url) = dictionary_url(&url, text) {
+            buttons.push(InlineKeyboardButton::url(
+                format!("Buscar en {}", search_url.host_str().unwrap_or_default()),
+                search_url,
+            ));
+        }
+    }
+
+    InlineKeyboardMarkup::new([buttons])
+}
+
+async fn send_dictionaries(
+    db_handler: DatabaseHandler,
+    bot: DLEBot,
+    msg: Message,
+    me: Me,
+) -> ResponseResult<()> {
+    let list = db_handler
+        .get_dictionaries()
+        .await
+        .iter()
+        .map(|dictionary| {
+            format!(
+                "— <b>{}</b>: {} (<code>@{} {}:palabra</code>)",
+                dictionary.short_name,
+                dictionary.name,
+                me.username(),
+                dictionary.code
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    bot.send_message(
+        msg.chat.id,
+        format!(
+            "Estos son los diccionarios disponibles, en el modo <i>inline</i> puedes buscar en uno solo con su prefijo:\n\n{}",
+            list
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+const REVERSE_SEARCH_LIMIT: u64 = 10;
+/// Number of similar lemmas suggested when a word is not found
+const FUZZY_SUGGESTIONS: usize = 5;
+
+async fn send_reverse_search(
+    db_handler: DatabaseHandler,
+    bot: DLEBot,
+    msg: Message,
+    description: &str,
+    me: Me,
+) -> ResponseResult<()> {
+    let description = description.trim();
+
+    if description.is_empty() {
+        bot.send_message(
+            msg.chat.id,
+            "Describe lo que buscas después del comando, por ejemplo: <code>/buscar animal que ladra</code>",
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let words = db_handler
+        .search_definitions(description, REVERSE_SEARCH_LIMIT)
+        .await;
+
+    let text = if words.is_empty() {
+        format!(
+            "No encontré palabras que coincidan con «{}».",
+            escape(description)
+        )
+    } else {
+        let list = words
+            .iter()
+            .map(|word| lemma_link(me.username(), &word.lemma))
+            .collect::<Vec<String>>()
+            .join("\n— ");
+        format!(
+            "Estas palabras coinciden con «{}»:\n\n— {}",
+            escape(description),
+            list
+        )
+    };
+
+    bot.send_message(msg.chat.id, text)
+        .link_preview_options(DISABLED_LINK_PREVIEW)
+        .await?;
+
+    Ok(())
+}
+
+/// Rebuild the indexes derived from the dictionary
+async fn reindex(db_handler: DatabaseHandler, bot: DLEBot, msg: Message) -> ResponseResult<()> {
+    let result = async {
+        Ok::<_, DbErr>((
+            db_handler.rebuild_inflection_index().await?,
+            db_handler.rebuild_phonetic_index().await?,
+            db_handler.rebuild_locution_index().await?,
+            db_handler.rebuild_word_game_keys().await?,
+        ))
+    }
+    .await;
+
+    let text = match result {
+        Ok((forms, lemmas, locutions, entries)) => format!(
+            "✅ Indexadas {} formas flexionadas, {} claves fonéticas, {} locuciones y las rimas y anagramas de {} entradas",
+            forms, lemmas, locutions, entries
+        ),
+        Err(error) => format!("Hubo un error con la base de datos: {}", error),
+    };
+
+    bot.send_message(msg.chat.id, text).await?;
+
+    Ok(())
+}
+
+/// Send the locution `text` as defined in the entry of `word`, with a button
+/// to see the whole entry. Returns whether it was found in the entry.
+async fn send_locution(
+    db_handler: &DatabaseHandler,
+    bot: &DLEBot,
+    msg: &Message,
+    user_id: i64,
+    word: DleModel,
+    text: &str,
+    me: &Me,
+) -> ResponseResult<bool> {
+    let Some(entry) = Entry::parse(&word.definition) else {
+        return Ok(false);
+    };
+    let Some(locution) = entry.find_locution(text) else {
+        return Ok(false);
+    };
+
+    let sources = db_handler.get_sources_of(&word.lemma).await;
+    let definition = with_source_badge(
+        &format!(
+            "{}\n<i>En la entrada</i> {}",
+            locution.to_html(),
+            lemma_link(me.username(), &word.lemma)
+        ),
+        &word,
+        &sources,
+    );
+
+    let request = bot
+        .send_message(msg.chat.id, definition)
+        .link_preview_options(DISABLED_LINK_PREVIEW);
+    let data = format!("{}{}:{}", SOURCE_CALLBACK_PREFIX, word.source, word.lemma);
+    if data.len() <= MAX_CALLBACK_DATA_LENGTH {
+        request
+            .reply_markup(InlineKeyboardMarkup::new([[
+                InlineKeyboardButton::callback(format!("Ver «{}»", word.lemma), data),
+            ]]))
+            .await?;
+    } else {
+        request.await?;
+    }
+
+    db_handler
+        .add_sent_definition_event(user_id, msg.date.into(), word.lemma)
+        .await;
+
+    Ok(true)
+}
+
+/// Switch between the compact and the full output format
+async fn toggle_compact(
+    db_handler: DatabaseHandler,
+    bot: DLEBot,
+    msg: Message,
+    user_id: i64,
+) -> ResponseResult<()> {
+    let (output_format, text) = match db_handler.get_output_format(user_id).await {
+        OutputFormat::Compact => (
+            OutputFormat::Full,
+            "Modo compacto desactivado, las definiciones se mostrarán completas.",
+        ),
+        OutputFormat::Full | OutputFormat::Image => (
+            OutputFormat::Compact,
+            "Modo compacto activado, las definiciones se mostrarán acepción por acepción. Usa /compacto de nuevo para desactivarlo.",
+        ),
+    };
+    db_handler.set_output_format(user_id, output_format).await;
+
+    bot.send_message(msg.chat.id, text).await?;
+
+    Ok(())
+}
+
+/// Inline keyboard with one button per lemma that sends its definition
+pub fn lemmas_keyboard(words: &[DleModel]) -> InlineKeyboardMarkup {
+    let buttons: Vec<InlineKeyboardButton> = words
+        .iter()
+        .map(|word| {
+            (
+                &word.lemma,
+                format!("{}{}:{}", SOURCE_CALLBACK_PREFIX, word.source, word.lemma),
+            )
+        })
+        .filter(|(_, data)| data.len() <= MAX_CALLBACK_DATA_LENGTH)
+        .map(|(lemma, data)| InlineKeyboardButton::callback(lemma, data))
+        .collect();
+
+    InlineKeyboardMarkup::new(buttons.chunks(3).map(|row| row.to_vec()))
+}
+
+/// Append the badge of the dictionary of `word` to `text`
+pub fn with_source_badge(text: &str, word: &DleModel, sources: &[DictionaryModel]) -> String {
+    match sources
+        .iter()
+        .find(|dictionary| dictionary.code == word.source)
+    {
+        Some(dictionary) => format!(
+            "{}\n\n{}",
+            text.trim_end(),
+            source_badge(dictionary, &word.lemma)
+        ),
+        None => text.to_string(),
+    }
+}
+
+/// Full definition of `word` split in messages, and the keyboard for the
+/// last of them
+pub fn full_definition(
+    word: &DleModel,
+    context: &DefinitionContext,
+    me: &Me,
+) -> (Vec<String>, Option<InlineKeyboardMarkup>) {
+    let definition = link_references(
+        &render_definition(&word.definition),
+        &context.references,
+        me.username(),
+    );
+    let definition = context.with_footer(&definition, word);
+
+    let parts = smart_split(&definition, MAX_MASSAGE_LENGTH)
+        .iter()
+        .enumerate()
+        .map(|(index, &part)| {
+            if index == 0 {
+                part.replacen(&word.lemma, &lemma_link(me.username(), &word.lemma), 1)
+            } else {
+                part.to_string()
+            }
+        })
+        .collect();
+
+    (parts, definition_keyboard(word, context))
+}
+
+/// Send the definition of `word` in the format chosen by the user, split in
+/// several messages if needed
+pub async fn send_definition(
+    db_handler: &DatabaseHandler,
+    bot: &DLEBot,
+    chat_id: ChatId,
+    user_id: i64,
+    date: DateTime<Utc>,
+    word: DleModel,
+    me: &Me,
+) -> ResponseResult<()> {
+    let context = DefinitionContext::load(db_handler, &word, user_id).await;
+
+    let output_format = db_handler.get_output_format(user_id).await;
+    let compact = if output_format == OutputFormat::Compact {
+        Entry::parse(&word.definition)
+            .and_then(|entry| compact_view(&word, &entry, 0, &context, me))
+    } else {
+        None
+    };
+    // Definitions too long for the caption of the image, or whose image
+    // can't be rendered, are sent as text
+    let image = output_format == OutputFormat::Image
+        && render_definition(&word.definition).chars().count() <= MAX_CAPTION_LENGTH;
+
+    if !(image && send_image(word.clone(), bot.clone(), chat_id, false).await?) {
+        if let Some((text, keyboard)) = compact {
+            bot.send_message(chat_id, text)
+                .link_preview_options(DISABLED_LINK_PREVIEW)
+                .reply_markup(keyboard)
+                .await?;
+        } else {
+            let (parts, keyboard) = full_definition(&word, &context, me);
+            let last_index = parts.len() - 1;
+            for (index, definition) in parts.into_iter().enumerate() {
+                let request = bot
+                    .send_message(chat_id, definition)
+                    .link_preview_options(DISABLED_LINK_PREVIEW);
+                match &keyboard {
+                    Some(keyboard) if index == last_index => {
+                        request.reply_markup(keyboard.clone()).await?;
+                    }
+                    _ => {
+                        request.await?;
+                    }
+                }
+            }
+        }
+    }
+
+    db_handler
+        .add_sent_definition_event(user_id, date.into(), word.lemma)
+        .await;
+
+    Ok(())
+}
+
 pub async fn send_message(
     db_handler: DatabaseHandler,
     bot: DLEBot,
@@ -138,73 +649,349 @@ pub async fn send_message(
     text: &str,
     me: Me,
 ) -> ResponseResult<()> {
-    match db_handler.get_exact(text).await {
-        Some(result) => {
-            for (index, &definition) in smart_split(&result.definition, MAX_MASSAGE_LENGTH)
-                .iter()
-                .enumerate()
-            {
-                let definition = if index == 0 {
-                    definition.replacen(
-                        &result.lemma,
-                        &format!(
-                            r#"<a href="https://t.me/{}?start={}">{}</a>"#,
-                            me.username(),
-                            base64_encode(result.lemma.to_string()),
-                            result.lemma
-                        ),
-                        1,
-                    )
+    send_lookup(db_handler, bot, msg, user_id, text, me, false).await
+}
+
+/// Feedback sent in reply to an edited message that now matches an entry
+const EDITED_FOUND: &str = "😌 ¡Ahora sí!";
+/// Feedback prepended to the answer to an edited message that still matches
+/// no entry
+const EDITED_NOT_FOUND: &str = "😐 Así tampoco";
+
+/// Look up `text` and answer with what was found. Answers to an `edited`
+/// message reply to it with feedback on whether the edit matches an entry.
+async fn send_lookup(
+    db_handler: DatabaseHandler,
+    bot: DLEBot,
+    msg: Message,
+    user_id: i64,
+    text: &str,
+    me: Me,
+    edited: bool,
+) -> ResponseResult<()> {
+    let send_found_feedback = || async {
+        if edited {
+            bot.send_message(msg.chat.id, EDITED_FOUND)
+                .reply_parameters(ReplyParameters::new(msg.id))
+                .await?;
+        }
+        ResponseResult::Ok(())
+    };
+    let not_found_feedback = if edited {
+        format!("{}\n\n", EDITED_NOT_FOUND)
+    } else {
+        "".to_string()
+    };
+    let reply_parameters = edited.then(|| ReplyParameters::new(msg.id));
+
+    let candidates = db_handler.get_accent_insensitive(text).await;
+    let written_as_is = candidates
+        .first()
+        .is_some_and(|word| word.lemma.to_lowercase() == text.to_lowercase());
+
+    if written_as_is || candidates.len() == 1 {
+        let mut candidates = candidates.into_iter();
+        if let Some(result) = candidates.next() {
+            send_found_feedback().await?;
+            send_definition(
+                &db_handler,
+                &bot,
+                msg.chat.id,
+                user_id,
+                msg.date,
+                result,
+                &me,
+            )
+            .await?;
+        }
+
+        let others: Vec<DleModel> = candidates.collect();
+        if !others.is_empty() {
+            bot.send_message(msg.chat.id, "Con otras tildes también está:")
+                .reply_markup(lemmas_keyboard(&others))
+                .await?;
+        }
+
+        return Ok(());
+    } else if !candidates.is_empty() {
+        send_found_feedback().await?;
+        bot.send_message(
+            msg.chat.id,
+            format!("¿Qué entrada buscas para «{}»?", escape(text)),
+        )
+        .reply_markup(lemmas_keyboard(&candidates))
+        .await?;
+
+        return Ok(());
+    }
+
+    let lemmas = db_handler.get_lemmas_of_form(text).await;
+    if !lemmas.is_empty() {
+        send_found_feedback().await?;
+        let list = lemmas
+            .iter()
+            .map(|word| format!("<i>{}</i>", word.lemma))
+            .collect::<Vec<String>>()
+            .join(" o ");
+        bot.send_message(
+            msg.chat.id,
+            format!("«{}» es una forma de {}.", escape(text), list),
+        )
+        .await?;
+
+        for word in lemmas {
+            send_definition(&db_handler, &bot, msg.chat.id, user_id, msg.date, word, &me).await?;
+        }
+
+        return Ok(());
+    }
+
+    let words: Vec<DleModel> = db_handler
+        .get_entries_of_locution(text)
+        .await
+        .into_iter()
+        .filter(|word| {
+            Entry::parse(&word.definition).is_some_and(|entry| entry.find_locution(text).is_some())
+        })
+        .collect();
+    if !words.is_empty() {
+        send_found_feedback().await?;
+        for word in words {
+            send_locution(&db_handler, &bot, &msg, user_id, word, text, &me).await?;
+        }
+
+        return Ok(());
+    }
+
+    let homophones = db_handler.get_homophones(text).await;
+    if !homophones.is_empty() {
+        let list = homophones
+            .iter()
+            .map(|word| {
+                let notes = homophone_notes(text, &word.lemma);
+                if notes.is_empty() {
+                    format!("— <i>{}</i>", word.lemma)
                 } else {
-                    definition.to_string()
-                };
-                bot.send_message(msg.chat.id, definition)
-                    .link_preview_options(DISABLED_LINK_PREVIEW)
-                    .await?;
-            }
+                    format!("— <i>{}</i>: {}.", word.lemma, notes.join("; "))
+                }
+            })
+            .collect::<Vec<String>>()
+            .join("\n");
+
+        let request = bot
+            .send_message(
+                msg.chat.id,
+                format!(
+                    "{}No encontré «{}», pero suena igual que:\n\n{}",
+                    not_found_feedback,
+                    escape(text),
+                    list
+                ),
+            )
+            .reply_markup(lemmas_keyboard(&homophones));
+        match reply_parameters {
+            Some(reply_parameters) => request.reply_parameters(reply_parameters).await?,
+            None => request.await?,
+        };
+
+        return Ok(());
+    }
+
+    let fuzzy_list = db_handler.get_fuzzy_list(text, FUZZY_SUGGESTIONS).await;
+
+    let similar_words = if fuzzy_list.is_empty() {
+        "".to_string()
+    } else {
+        let list = fuzzy_list
+            .iter()
+            .map(|x| lemma_link(me.username(), x))
+            .collect::<Vec<String>>()
+            .join("\n— ");
+        format!("Estas son algunas entradas parecidas:\n\n— {}", list)
+    };
+
+    let inline_keyboard = not_found_keyboard(&db_handler, text).await;
+
+    let request = bot
+        .send_message(
+            msg.chat.id,
+            format!(
+                "{}{}",
+                not_found_feedback,
+                format_args!(
+                    include_str!("templates/not_found.txt"),
+                    escape(text),
+                    similar_words
+                )
+            ),
+        )
+        .link_preview_options(DISABLED_LINK_PREVIEW)
+        .reply_markup(inline_keyboard);
+    match reply_parameters {
+        Some(reply_parameters) => request.reply_parameters(reply_parameters).await?,
+        None => request.await?,
+    };
+
+    Ok(())
+}
 
-            db_handler
-                .add_sent_definition_event(user_id, msg.date.into(), result.lemma)
-                .await;
+async fn handle_command(
+    db_handler: DatabaseHandler,
+    bot: DLEBot,
+    msg: Message,
+    user_id: i64,
+    command: Command,
+    me: Me,
+) -> ResponseResult<()> {
+    match command {
+        Command::Start(start_parameter) => match base64_decode(start_parameter.clone()) {
+            Ok(decoded) => match decoded.as_ref() {
+                "" => {
+                    send_start(bot, msg).await?;
+                }
+                _ => {
+                    send_message(db_handler, bot, msg, user_id, &decoded, me).await?;
+                }
+            },
+            _ => {
+                log::warn!("Failed to decode start_parameter {}", start_parameter);
+                send_start(bot, msg).await?;
+            }
+        },
+        Command::Help | Command::Ayuda => {
+            send_help(bot, msg, me).await?;
         }
-        None => {
-            let fuzzy_list = db_handler.get_fuzzy_list(text).await;
+        Command::Aleatorio => {
+            send_random(db_handler, bot, msg).await?;
+        }
+        Command::Pdd => {
+            send_word_of_the_day(db_handler, bot, msg).await?;
+        }
+        Command::Def(word) => {
+            send_def(db_handler, bot, msg, user_id, &word, me).await?;
+        }
+        Command::Conjugar(verb) => {
+            send_conjugation(db_handler, bot, msg.chat.id, &verb).await?;
+        }
+        Command::Buscar(description) => {
+            send_reverse_search(db_handler, bot, msg, &description, me).await?;
+        }
+        Command::Diccionarios => {
+            send_dictionaries(db_handler, bot, msg, me).await?;
+        }
+        Command::Abreviatura(abbreviation) => {
+            send_abbreviation(&db_handler, &bot, msg.chat.id, &abbreviation).await?;
+        }
+        Command::Sinonimos(lemma) => {
+            send_relations(&db_handler, &bot, msg.chat.id, &lemma, &me).await?;
+        }
+        Command::Patron(query) => {
+            send_pattern_matches(&db_handler, &bot, msg.chat.id, &query, &me).await?;
+        }
+        Command::Anagramas(word) => {
+            send_anagrams(&db_handler, &bot, msg.chat.id, &word, &me).await?;
+        }
+        Command::Rima(word) => {
+            send_rhymes(&db_handler, &bot, msg.chat.id, &word, &me).await?;
+        }
+        Command::Silabas(text) => {
+            send_syllables(&bot, msg.chat.id, &text).await?;
+        }
+        Command::Corregir(text) => {
+            send_spelling_check(&db_handler, &bot, msg.chat.id, &text, &me).await?;
+        }
+        Command::Compacto => {
+            toggle_compact(db_handler, bot, msg, user_id).await?;
+        }
+        Command::Ajustes => {
+            send_settings(&db_handler, &bot, &msg, user_id, MENU).await?;
+        }
+        Command::Suscripcion => {
+            send_settings(&db_handler, &bot, &msg, user_id, SUBSCRIPTION).await?;
+        }
+        Command::Hora(text) => {
+            send_delivery_settings(&db_handler, &bot, msg.chat.id, user_id, &text).await?;
+        }
+        Command::Configurar => {
+            send_chat_settings(&db_handler, &bot, &msg).await?;
+        }
+    }
 
-            let similar_words = if fuzzy_list.is_empty() {
-                "".to_string()
-            } else {
-                let list = fuzzy_list
-                    .iter()
-                    .map(|x| {
-                        format!(
-                            r#"<a href="https://t.me/{}?start={}">{}</a>"#,
-                            me.username(),
-                            base64_encode(x.to_string()),
-                            x
-                        )
-                    })
-                    .collect::<Vec<String>>()
-                    .join("\n— ");
-                format!("Estas son algunas entradas parecidas:\n\n— {}", list)
-            };
-
-            let url = match reqwest::Url::parse(&format!("https://dle.rae.es/{}", text)) {
-                Ok(value) => value,
-                Err(_) => reqwest::Url::parse("https://dle.rae.es/").unwrap(),
-            };
-
-            let inline_keyboard = InlineKeyboardMarkup::new([[
-                InlineKeyboardButton::switch_inline_query_current_chat("Probar inline", ""),
-                InlineKeyboardButton::url("Buscar en dle.rae.es", url),
-            ]]);
+    Ok(())
+}
+
+/// Look up `word`, or let the user pick a word of the message `msg` replies
+/// to if `word` is empty
+async fn send_def(
+    db_handler: DatabaseHandler,
+    bot: DLEBot,
+    msg: Message,
+    user_id: i64,
+    word: &str,
+    me: Me,
+) -> ResponseResult<()> {
+    let word = word.trim();
+
+    if !word.is_empty() {
+        return send_message(db_handler, bot, msg, user_id, word, me).await;
+    }
 
+    let replied_text = msg
+        .reply_to_message()
+        .and_then(|reply| reply.text().or(reply.caption()))
+        .map(str::to_string);
+
+    match replied_text {
+        Some(text) => send_word_picker(db_handler, bot, msg, user_id, &text, me).await,
+        None => {
             bot.send_message(
                 msg.chat.id,
-                format!(include_str!("templates/not_found.txt"), text, similar_words),
+                "Escribe la palabra después del comando, por ejemplo: <code>/def casa</code>, o responde con /def al mensaje que quieras buscar.",
             )
-            .link_preview_options(DISABLED_LINK_PREVIEW)
-            .reply_markup(inline_keyboard)
             .await?;
+            Ok(())
+        }
+    }
+}
+
+/// In groups only `/def`, `/configurar` and messages that start mentioning
+/// the bot are always answered. The rest of the commands can be disabled by
+/// the administrators, and any other message is ignored.
+async fn handle_group_message(
+    db_handler: DatabaseHandler,
+    bot: DLEBot,
+    msg: Message,
+    user_id: i64,
+    command: Result<Command, ParseError>,
+    text: &str,
+    me: Me,
+) -> ResponseResult<()> {
+    match command {
+        Ok(command @ (Command::Def(_) | Command::Configurar)) => {
+            handle_command(db_handler, bot, msg, user_id, command, me).await?;
+        }
+        // The reply keyboard of /start only makes sense in a private chat
+        Ok(Command::Start(_)) => {}
+        Ok(command) => {
+            if db_handler.get_chat(msg.chat.id.0).await.commands_enabled {
+                handle_command(db_handler, bot, msg, user_id, command, me).await?;
+            }
+        }
+        Err(_) => {
+            let mention = format!("@{}", me.username());
+            // `@botnamexyz` is the mention of another account
+            let mentioned = text
+                .get(..mention.len())
+                .is_some_and(|start| start.eq_ignore_ascii_case(&mention))
+                && text[mention.len()..]
+                    .chars()
+                    .next()
+                    .is_none_or(char::is_whitespace);
+
+            if mentioned {
+                let word = text[mention.len()..].to_string();
+                send_def(db_handler, bot, msg, user_id, &word, me).await?;
+            }
         }
     }
 
@@ -219,19 +1006,24 @@ pub async fn handle_message(
 ) -> ResponseResult<()> {
     if let Some(user) = msg.clone().from {
         if let Ok(user_id) = user.id.0.try_into() {
-            db_handler.set_in_bot(user_id, true).await;
+            if msg.chat.is_private() {
+                db_handler.set_in_bot(user_id, true).await;
+            }
 
             match msg.via_bot {
                 Some(via_bot) if via_bot.id == me.id => return Ok(()),
 
                 _ => {
-                    db_handler
-                        .add_message_event(
-                            user_id,
-                            msg.date.into(),
-                            msg.text().unwrap_or("").to_string(),
-                        )
-                        .await;
+                    // Messages in groups aren't always meant for the bot
+                    if msg.chat.is_private() {
+                        db_handler
+                            .add_message_event(
+                                user_id,
+                                msg.date.into(),
+                                msg.text().unwrap_or("").to_string(),
+                            )
+                            .await;
+                    }
 
                     if let Some(text) = msg.clone().text() {
                         match BotCommands::parse(text, me.username()) {
@@ -317,45 +1109,51 @@ pub async fn handle_message(
 
                                 return Ok(());
                             }
-                            _ => {}
-                        }
+                            Ok(AdminCommand::Reindexar) if db_handler.is_admin(user_id).await => {
+                                reindex(db_handler, bot, msg).await?;
 
-                        match BotCommands::parse(text, me.username()) {
-                            Ok(Command::Start(start_parameter)) => {
-                                match base64_decode(start_parameter.clone()) {
-                                    Ok(decoded) => match decoded.as_ref() {
-                                        "" => {
-                                            send_start(bot, msg).await?;
-                                        }
-                                        _ => {
-                                            send_message(
-                                                db_handler, bot, msg, user_id, &decoded, me,
-                                            )
-                                            .await?;
-                                        }
-                                    },
-                                    _ => {
-                                        log::warn!(
-                                            "Failed to decode start_parameter {}",
-                                            start_parameter
-                                        );
-                                        send_start(bot, msg).await?;
-                                    }
-                                }
+                                return Ok(());
                             }
+                            Ok(AdminCommand::Targets) if db_handler.is_admin(user_id).await => {
+                                send_targets(&db_handler, &bot, msg.chat.id).await?;
 
-                            Ok(Command::Help | Command::Ayuda) => {
-                                send_help(bot, msg, me).await?;
+                                return Ok(());
                             }
+                            Ok(AdminCommand::AddTarget(text))
+                                if db_handler.is_admin(user_id).await =>
+                            {
+                                add_target(&db_handler, &bot, msg.chat.id, &text).await?;
 
-                            Ok(Command::Aleatorio) => {
-                                send_random(db_handler, bot, msg).await?;
+                                return Ok(());
                             }
+                            Ok(AdminCommand::SetTarget(text))
+                                if db_handler.is_admin(user_id).await =>
+                            {
+                                set_target(&db_handler, &bot, msg.chat.id, &text).await?;
 
-                            Ok(Command::Pdd) => {
-                                send_word_of_the_day(db_handler, bot, msg).await?;
+                                return Ok(());
                             }
+                            Ok(AdminCommand::DelTarget(text))
+                                if db_handler.is_admin(user_id).await =>
+                            {
+                                delete_target(&db_handler, &bot, msg.chat.id, &text).await?;
+
+                                return Ok(());
+                            }
+                            _ => {}
+                        }
+
+                        let command = BotCommands::parse(text, me.username());
+                        if is_group(&msg.chat) {
+                            handle_group_message(db_handler, bot, msg, user_id, command, text, me)
+                                .await?;
+                            return Ok(());
+                        }
 
+                        match command {
+                            Ok(command) => {
+                                handle_command(db_handler, bot, msg, user_id, command, me).await?;
+                            }
                             Err(_) => match text {
                                 KEY_RANDOM => {
                                     send_random(db_handler, bot, msg).await?;
@@ -383,7 +1181,13 @@ pub async fn handle_edited_message(
     db_handler: DatabaseHandler,
     bot: DLEBot,
     msg: Message,
+    me: Me,
 ) -> ResponseResult<()> {
+    // Edits in groups are usually not meant for the bot
+    if !msg.chat.is_private() {
+        return Ok(());
+    }
+
     if let Some(user) = msg.clone().from {
         if let Ok(user_id) = user.id.0.try_into() {
             db_handler.set_in_bot(user_id, true).await;
@@ -397,44 +1201,7 @@ pub async fn handle_edited_message(
                     )
                     .await;
 
-                match db_handler.get_exact(text).await {
-                    Some(result) => {
-                        for definition in smart_split(&result.definition, MAX_MASSAGE_LENGTH) {
-                            bot.send_message(
-                                msg.chat.id,
-                                format!("😌 ¡Ahora sí!\n\n{}", definition.trim()),
-                            )
-                            .reply_parameters(ReplyParameters::new(msg.id))
-                            .await?;
-                        }
-
-                        db_handler
-                            .add_sent_definition_event(user_id, msg.date.into(), result.lemma)
-                            .await;
-                    }
-                    None => {
-                        let url = match reqwest::Url::parse(&format!("https://dle.rae.es/{}", text))
-                        {
-                            Ok(value) => value,
-                            Err(_) => reqwest::Url::parse("https://dle.rae.es/").unwrap(),
-                        };
-
-                        let inline_keyboard = InlineKeyboardMarkup::new([[
-                            InlineKeyboardButton::switch_inline_query_current_chat(
-                                "Probar inline",
-                                "",
-                            ),
-                            InlineKeyboardButton::url("Buscar en dle.rae.es", url),
-                        ]]);
-
-                        let text = format!(include_str!("templates/not_found.txt"), text, "");
-
-                        bot.send_message(msg.chat.id, format!("😐 Así tampoco\n\n{}", text))
-                            .reply_markup(inline_keyboard)
-                            .reply_parameters(ReplyParameters::new(msg.id))
-                            .await?;
-                    }
-                }
+                send_lookup(db_handler, bot, msg.clone(), user_id, text, me, true).await?;
             }
         }
     }
@@ -1,14 +1,16 @@
 use std::path::Path;
 
 use ::teloxide::{prelude::*, types::InputFile};
-use chrono::{offset::Local, Datelike};
+use chrono::{Datelike, NaiveDate, Utc};
 use rand::Rng;
 use regex::Regex;
 use usvg::{fontdb, TreeParsing, TreeTextToPath};
 
 use crate::{
     database::DleModel,
-    utils::{base64_encode, split_by_whitespace},
+    entry::{render_definition, Entry},
+    utils::{lemma_link, split_by_whitespace},
+    word_of_the_day::schedule_timezone,
     DLEBot,
 };
 
@@ -26,11 +28,47 @@ const FONT_SCALE: f64 = 0.9;
 const FONT_SIZE_NORMAL: f64 = 5.0 * FONT_SCALE;
 const FONT_SIZE_BIG: f64 = 20.0 * FONT_SCALE;
 
-fn get_image(lemma: &str, etymology: &str, channel: &str) -> Result<Vec<u8>, png::EncodingError> {
+/// Colors of the image
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Theme {
+    /// Light or dark, chosen at random each time
+    Random,
+    Light,
+    Dark,
+}
+
+impl Theme {
+    pub const ALL: [Theme; 3] = [Theme::Random, Theme::Light, Theme::Dark];
+
+    /// Name of the theme as stored in the database
+    pub fn name(&self) -> &'static str {
+        match self {
+            Theme::Random => "random",
+            Theme::Light => "light",
+            Theme::Dark => "dark",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Theme> {
+        Theme::ALL.into_iter().find(|theme| theme.name() == name)
+    }
+}
+
+fn get_image(
+    lemma: &str,
+    etymology: &str,
+    channel: &str,
+    date: NaiveDate,
+    theme: Theme,
+) -> Result<Vec<u8>, png::EncodingError> {
     let mut rng = rand::thread_rng();
 
     let bg_index = rng.gen_range(0..BG_COLORS_LENGTH);
-    let dark_theme = rng.gen_bool(0.5);
+    let dark_theme = match theme {
+        Theme::Random => rng.gen_bool(0.5),
+        Theme::Light => false,
+        Theme::Dark => true,
+    };
 
     let bg_color = if dark_theme {
         DARK_BG_COLORS[bg_index]
@@ -41,7 +79,6 @@ fn get_image(lemma: &str, etymology: &str, channel: &str) -> Result<Vec<u8>, png
     let date = if channel.is_empty() {
         "".to_string()
     } else {
-        let date = Local::now();
         format!("{}/{}/{}", date.day(), date.month(), date.year())
     };
 
@@ -130,21 +167,39 @@ fn fix_tags(input: &mut [String]) {
     }
 }
 
+/// Send the image of `word` dated today in the time zone of the schedule of
+/// the word of the day, see `send_themed_image`
 pub async fn send_image(
     word: DleModel,
     bot: DLEBot,
     chat_id: ChatId,
     pdd: bool,
-) -> ResponseResult<()> {
-    let mut split = word.definition.trim_start().split('\n');
-    let lemma = split.next().unwrap().trim().convert_html_tags_to_svg();
-    let mut etymology = split.next().unwrap().trim();
-    if etymology.is_empty() {
-        etymology = split.next().unwrap().trim();
-    }
+) -> ResponseResult<bool> {
+    let caption_prefix = if pdd { "📖 #PalabraDelDía |" } else { "" };
+
+    let today = Utc::now().with_timezone(&schedule_timezone()).date_naive();
+
+    send_themed_image(word, bot, chat_id, caption_prefix, today, Theme::Random).await
+}
+
+/// Send the image of `word` in the colors of `theme`, with its definition as
+/// caption after `caption_prefix`. Images sent to channels show `date`.
+/// Returns `false` if the image couldn't be rendered, so nothing was sent.
+pub async fn send_themed_image(
+    word: DleModel,
+    bot: DLEBot,
+    chat_id: ChatId,
+    caption_prefix: &str,
+    date: NaiveDate,
+    theme: Theme,
+) -> ResponseResult<bool> {
+    let (lemma, etymology) = match Entry::parse(&word.definition) {
+        Some(entry) => (entry.lemma, entry.etymology.unwrap_or_default()),
+        None => (word.lemma.clone(), String::new()),
+    };
 
     let dy = INTERLINE_SPACING * FONT_SIZE_NORMAL;
-    let mut etymology_lines: Vec<String> = split_by_whitespace(etymology, MAX_CHARACTERS_IN_LINE)
+    let mut etymology_lines: Vec<String> = split_by_whitespace(&etymology, MAX_CHARACTERS_IN_LINE)
         .iter()
         .map(|&line| line.convert_html_tags_to_svg())
         .collect();
@@ -155,14 +210,10 @@ pub async fn send_image(
         etymology += &format!(r#"<tspan x="10" dy="{}">{}</tspan>"#, i as f64 * dy, line);
     }
 
-    let definition = word.definition.replacen(
-        &lemma,
-        &format!(
-            r#"<a href="https://t.me/{}?start={}">{}</a>"#,
-            bot.get_me().await.unwrap().username(),
-            base64_encode(word.lemma.to_string()),
-            lemma
-        ),
+    let me = bot.get_me().await?;
+    let definition = render_definition(&word.definition).replacen(
+        &word.lemma,
+        &lemma_link(me.username(), &word.lemma),
         1,
     );
 
@@ -175,15 +226,16 @@ pub async fn send_image(
         }
     }
 
-    if let Ok(image) = get_image(&lemma, &etymology, &channel) {
-        bot.send_photo(chat_id, InputFile::memory(image))
-            .caption(format!(
-                "{} {}",
-                if pdd { "📖 #PalabraDelDía |" } else { "" },
-                definition.trim()
-            ))
-            .await?;
+    match get_image(&lemma, &etymology, &channel, date, theme) {
+        Ok(image) => {
+            bot.send_photo(chat_id, InputFile::memory(image))
+                .caption(format!("{} {}", caption_prefix, definition.trim()))
+                .await?;
+            Ok(true)
+        }
+        Err(error) => {
+            log::error!("Error rendering the image of {}: {:?}", word.lemma, error);
+            Ok(false)
+        }
     }
-
-    Ok(())
 }
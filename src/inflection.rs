@@ -0,0 +1,221 @@
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use crate::{conjugation::Conjugation, database::DleModel};
+
+const STRONG_VOWELS: &str = "aeoáéíóú";
+
+static TAGS: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"<[^>]*>").unwrap());
+/// Grammatical marks of nouns and adjectives, which have plurals
+static NOMINAL: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?:^|\s)(?:adj|m|f|com)\.").unwrap());
+
+fn is_vowel(c: char) -> bool {
+    "aeiouáéíóúü".contains(c)
+}
+
+fn remove_tilde(c: char) -> char {
+    match c {
+        'á' => 'a',
+        'é' => 'e',
+        'í' => 'i',
+        'ó' => 'o',
+        'ú' => 'u',
+        _ => c,
+    }
+}
+
+fn add_tilde(c: char) -> char {
+    match c {
+        'a' => 'á',
+        'e' => 'é',
+        'i' => 'í',
+        'o' => 'ó',
+        'u' => 'ú',
+        _ => c,
+    }
+}
+
+fn has_tilde(word: &str) -> bool {
+    word.chars().any(|c| "áéíóú".contains(c))
+}
+
+fn without_tilde(word: &str) -> String {
+    word.chars().map(remove_tilde).collect()
+}
+
+/// Char ranges of the vowel nuclei of `word`, hiatus between strong vowels
+/// start a new nucleus.
+fn vowel_nuclei(chars: &[char]) -> Vec<(usize, usize)> {
+    let mut nuclei: Vec<(usize, usize)> = vec![];
+    let mut previous_vowel: Option<char> = None;
+
+    for (i, &c) in chars.iter().enumerate() {
+        if is_vowel(c) {
+            let hiatus = previous_vowel
+                .map(|p| STRONG_VOWELS.contains(p) && STRONG_VOWELS.contains(c))
+                .unwrap_or(true);
+            if hiatus {
+                nuclei.push((i, i + 1));
+            } else if let Some(last) = nuclei.last_mut() {
+                last.1 = i + 1;
+            }
+            previous_vowel = Some(c);
+        } else {
+            previous_vowel = None;
+        }
+    }
+
+    nuclei
+}
+
+/// Put a written accent on the second to last syllable of `word`,
+/// used when a plural turns a *llana* into an *esdrújula*
+fn accent_penultimate(word: &str) -> String {
+    let mut chars: Vec<char> = word.chars().collect();
+    let nuclei = vowel_nuclei(&chars);
+
+    if nuclei.len() >= 2 {
+        let (start, end) = nuclei[nuclei.len() - 2];
+        let position = (start..end)
+            .find(|&i| "aeo".contains(chars[i]))
+            .unwrap_or(end - 1);
+        chars[position] = add_tilde(chars[position]);
+    }
+
+    chars.into_iter().collect()
+}
+
+/// Plural forms of a noun or adjective following the general rules of the
+/// *Ortografía*. Irregular plurals are not covered.
+pub fn plurals(word: &str) -> Vec<String> {
+    let chars: Vec<char> = word.chars().collect();
+    let syllables = vowel_nuclei(&chars).len();
+
+    let Some(&last) = chars.last() else {
+        return vec![];
+    };
+    let stem: String = chars[..chars.len() - 1].iter().collect();
+
+    match last {
+        'í' | 'ú' => vec![format!("{}es", word), format!("{}s", word)],
+        c if is_vowel(c) => vec![format!("{}s", word)],
+        'z' => vec![format!("{}ces", stem)],
+        's' | 'x' => {
+            if syllables == 1 {
+                vec![format!("{}es", word)]
+            } else if chars.len() >= 2 && "áéíóú".contains(chars[chars.len() - 2]) {
+                // Aguda: autobús -> autobuses
+                vec![format!("{}es", without_tilde(word))]
+            } else {
+                // Llana or esdrújula: invariable
+                vec![]
+            }
+        }
+        'n' => {
+            if syllables == 1 {
+                vec![format!("{}es", word)]
+            } else if has_tilde(word) {
+                if chars.len() >= 2 && "áéíóú".contains(chars[chars.len() - 2]) {
+                    // Aguda: canción -> canciones
+                    vec![format!("{}es", without_tilde(word))]
+                } else {
+                    vec![format!("{}es", word)]
+                }
+            } else {
+                // Llana: examen -> exámenes
+                vec![format!("{}es", accent_penultimate(word))]
+            }
+        }
+        'l' | 'r' | 'd' | 'j' | 'y' => vec![format!("{}es", word)],
+        _ => vec![format!("{}s", word)],
+    }
+}
+
+/// Feminine of `lemma` from a DLE headword like `niño, ña` or `actor, triz`.
+pub fn feminine(lemma: &str, ending: &str) -> Option<String> {
+    let first = ending.chars().next()?;
+    let position = lemma.rfind(first)?;
+    let feminine = format!("{}{}", &lemma[..position], ending);
+
+    // The feminine adds a syllable so agudas lose their written accent:
+    // león, na -> leona
+    let lemma_last = lemma.chars().last()?;
+    if has_tilde(lemma) && !is_vowel(lemma_last) && is_vowel(ending.chars().last()?) {
+        let chars: Vec<char> = lemma.chars().collect();
+        let nuclei = vowel_nuclei(&chars);
+        if let Some(&(start, end)) = nuclei.last() {
+            if chars[start..end].iter().any(|c| "áéíóú".contains(*c)) {
+                return Some(without_tilde(&feminine));
+            }
+        }
+    }
+
+    Some(feminine)
+}
+
+/// Inflected forms of a dictionary entry: every simple form of its
+/// conjugation for verbs, feminine and plurals for nouns and adjectives.
+/// The lemma itself is not included and all forms are lowercase.
+pub fn inflected_forms(word: &DleModel) -> Vec<String> {
+    let lemma = word.lemma.to_lowercase();
+    let mut forms: Vec<String> = vec![];
+
+    if let Some(conjugation) = Conjugation::from_word(word) {
+        forms.extend(conjugation.forms());
+    }
+
+    let definition = TAGS.replace_all(&word.definition, "");
+    let mut lines = definition.trim_start().lines();
+    let header = lines.next().unwrap_or_default().trim().to_lowercase();
+
+    if NOMINAL.is_match(&definition) {
+        let mut singulars = vec![lemma.clone()];
+        if let Some((masculine, ending)) = header.split_once(", ") {
+            if masculine.trim() == lemma && !ending.contains(' ') {
+                if let Some(feminine) = feminine(&lemma, ending.trim()) {
+                    forms.push(feminine.clone());
+                    singulars.push(feminine);
+                }
+            }
+        }
+
+        for singular in singulars {
+            forms.extend(plurals(&singular));
+        }
+    }
+
+    forms.sort();
+    forms.dedup();
+    forms.retain(|form| *form != lemma);
+
+    forms
+}
+
+#[test]
+fn test_plurals() {
+    assert_eq!(plurals("casa"), ["casas"]);
+    assert_eq!(plurals("sofá"), ["sofás"]);
+    assert_eq!(plurals("rubí"), ["rubíes", "rubís"]);
+    assert_eq!(plurals("luz"), ["luces"]);
+    assert_eq!(plurals("lápiz"), ["lápices"]);
+    assert_eq!(plurals("mes"), ["meses"]);
+    assert_eq!(plurals("autobús"), ["autobuses"]);
+    assert!(plurals("crisis").is_empty());
+    assert_eq!(plurals("canción"), ["canciones"]);
+    assert_eq!(plurals("examen"), ["exámenes"]);
+    assert_eq!(plurals("joven"), ["jóvenes"]);
+    assert_eq!(plurals("pan"), ["panes"]);
+    assert_eq!(plurals("árbol"), ["árboles"]);
+    assert_eq!(plurals("rey"), ["reyes"]);
+}
+
+#[test]
+fn test_feminine() {
+    assert_eq!(feminine("niño", "ña").unwrap(), "niña");
+    assert_eq!(feminine("profesor", "ra").unwrap(), "profesora");
+    assert_eq!(feminine("actor", "triz").unwrap(), "actriz");
+    assert_eq!(feminine("león", "na").unwrap(), "leona");
+    assert_eq!(feminine("alemán", "na").unwrap(), "alemana");
+}
@@ -1,25 +1,50 @@
+mod abbreviation;
 mod broadcast;
+mod chat_settings;
+mod compact;
+mod conjugation;
+mod cross_reference;
 mod database;
+mod delivery;
+mod entry;
+mod fuzzy;
 mod handle_callback_query;
 mod handle_chat_member;
 mod handle_inline;
 mod handle_message;
 mod image;
+mod inflection;
+mod pattern;
+mod phonetic;
+mod relation;
+mod rhyme;
+mod settings;
+mod spelling;
+mod syllable;
 mod utils;
 mod word_of_the_day;
+mod word_picker;
+mod wotd_target;
 
+use chrono::{Duration, NaiveTime};
+use chrono_tz::Tz;
 use dotenvy::dotenv;
 use teloxide::{adaptors::DefaultParseMode, prelude::*, update_listeners::webhooks};
 
-use database::DatabaseHandler;
+use database::{DatabaseHandler, DEFAULT_TIMEZONE};
+use delivery::schedule_deliveries;
 use handle_callback_query::handle_callback_query;
 use handle_chat_member::handle_my_chat_member;
 use handle_inline::{handle_chosen_inline_result, handle_inline};
 use handle_message::{handle_edited_message, handle_message, set_commands};
-use word_of_the_day::schedule_word_of_the_day;
+use word_of_the_day::{schedule_timezone, schedule_word_of_the_day};
 
 pub type DLEBot = DefaultParseMode<Bot>;
 
+/// Minutes after the scheduled time during which a missed word of the day is
+/// still posted, unless `SCHEDULE_GRACE_MINUTES` says otherwise
+const DEFAULT_SCHEDULE_GRACE_MINUTES: i64 = 60;
+
 #[tokio::main]
 async fn main() -> ResponseResult<()> {
     dotenv().ok();
@@ -38,6 +63,31 @@ async fn main() -> ResponseResult<()> {
         .parse::<u32>()
         .unwrap();
 
+    let schedule_time = NaiveTime::from_hms_opt(schedule_hour, schedule_min, 0).unwrap();
+
+    if let Ok(timezone) = std::env::var("SCHEDULE_TIMEZONE") {
+        if timezone.parse::<Tz>().is_err() {
+            log::warn!(
+                "Invalid SCHEDULE_TIMEZONE {:?}, using {}",
+                timezone,
+                DEFAULT_TIMEZONE
+            );
+        }
+    }
+    let schedule_timezone = schedule_timezone();
+
+    let schedule_grace = Duration::minutes(match std::env::var("SCHEDULE_GRACE_MINUTES") {
+        Ok(grace) => grace.parse::<i64>().unwrap_or_else(|_| {
+            log::warn!(
+                "Invalid SCHEDULE_GRACE_MINUTES {:?}, using {}",
+                grace,
+                DEFAULT_SCHEDULE_GRACE_MINUTES
+            );
+            DEFAULT_SCHEDULE_GRACE_MINUTES
+        }),
+        Err(_) => DEFAULT_SCHEDULE_GRACE_MINUTES,
+    });
+
     let api_url = std::env::var("TELEGRAM_BOT_API_URL").unwrap();
     let api_url = reqwest::Url::parse(&api_url).unwrap();
 
@@ -64,10 +114,13 @@ async fn main() -> ResponseResult<()> {
     let scheduler_handle = tokio::spawn(schedule_word_of_the_day(
         db_handler.clone(),
         bot.clone(),
-        schedule_hour,
-        schedule_min,
+        schedule_time,
+        schedule_timezone,
+        schedule_grace,
     ));
 
+    let deliveries_handle = tokio::spawn(schedule_deliveries(db_handler.clone(), bot.clone()));
+
     let handler = dptree::entry()
         .branch(Update::filter_message().endpoint(handle_message))
         .branch(Update::filter_edited_message().endpoint(handle_edited_message))
@@ -87,6 +140,7 @@ async fn main() -> ResponseResult<()> {
         .await;
 
     scheduler_handle.abort();
+    deliveries_handle.abort();
 
     Ok(())
 }
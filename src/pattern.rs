@@ -0,0 +1,279 @@
+use teloxide::{
+    prelude::*,
+    types::{InlineKeyboardButton, InlineKeyboardMarkup, Me, MessageId},
+};
+
+use crate::{
+    database::DatabaseHandler,
+    utils::{
+        lemma_link, unaccent, DISABLED_LINK_PREVIEW, MAX_CALLBACK_DATA_LENGTH,
+        PATTERN_CALLBACK_PREFIX,
+    },
+    DLEBot,
+};
+
+/// Maximum number of characters of a pattern
+const MAX_PATTERN_LENGTH: usize = 50;
+/// Number of lemmas listed in each page of `/patron`
+const PATTERN_PAGE_SIZE: u64 = 30;
+/// Option that makes the written accents of the pattern count
+const EXACT_OPTION: &str = "exacto";
+
+const PATTERN_USAGE: &str = "Escribe el patrón después del comando: <code>?</code> es una letra cualquiera y <code>*</code> cualquier número de letras. Puedes añadir la longitud (<code>7</code>, <code>5-8</code>, <code>5-</code> o <code>-8</code>) y <code>exacto</code> para distinguir tildes, por ejemplo: <code>/patron c*ón 7</code>";
+
+/// Crossword-like pattern over lemmas: letters, `?` for exactly one letter
+/// and `*` for any number of them, with optional length bounds
+#[derive(Debug, PartialEq)]
+pub struct Pattern {
+    /// Pattern for SQL `LIKE`, in lowercase and without written accents if
+    /// `accent_insensitive`
+    pub like: String,
+    pub min_length: Option<u32>,
+    pub max_length: Option<u32>,
+    pub accent_insensitive: bool,
+}
+
+impl Pattern {
+    /// Parse queries like `c*ón 7`, `c?s?` or `*ción 5-8 exacto`. Only
+    /// letters, `-` and the wildcards are accepted, so the result is safe to
+    /// use in `LIKE` without escaping.
+    pub fn parse(query: &str) -> Option<Pattern> {
+        let mut tokens = query.split_whitespace();
+        let pattern = tokens.next()?.to_lowercase();
+
+        if pattern.chars().count() > MAX_PATTERN_LENGTH
+            || !pattern
+                .chars()
+                .all(|c| c.is_alphabetic() || matches!(c, '?' | '*' | '-'))
+        {
+            return None;
+        }
+
+        let mut min_length = None;
+        let mut max_length = None;
+        let mut accent_insensitive = true;
+
+        for token in tokens {
+            if token.to_lowercase() == EXACT_OPTION {
+                accent_insensitive = false;
+            } else if let Some((min, max)) = token.split_once('-') {
+                min_length = if min.is_empty() {
+                    None
+                } else {
+                    Some(min.parse().ok()?)
+                };
+                max_length = if max.is_empty() {
+                    None
+                } else {
+                    Some(max.parse().ok()?)
+                };
+            } else {
+                let length = token.parse().ok()?;
+                min_length = Some(length);
+                max_length = Some(length);
+            }
+        }
+
+        if min_length
+            .zip(max_length)
+            .is_some_and(|(min, max)| min > max)
+        {
+            return None;
+        }
+        // Only wildcards and no length would list the whole dictionary
+        if pattern.contains('*')
+            && !pattern.chars().any(char::is_alphabetic)
+            && min_length.is_none()
+            && max_length.is_none()
+        {
+            return None;
+        }
+
+        let pattern = if accent_insensitive {
+            unaccent(&pattern)
+        } else {
+            pattern
+        };
+
+        Some(Pattern {
+            like: pattern.replace('?', "_").replace('*', "%"),
+            min_length,
+            max_length,
+            accent_insensitive,
+        })
+    }
+
+    /// Length bounds as text, to be added after the pattern
+    fn length_description(&self) -> String {
+        match (self.min_length, self.max_length) {
+            (Some(min), Some(max)) if min == max => format!(" de {} letras", min),
+            (Some(min), Some(max)) => format!(" de {} a {} letras", min, max),
+            (Some(min), None) => format!(" de {} letras o más", min),
+            (None, Some(max)) => format!(" de {} letras o menos", max),
+            (None, None) => String::new(),
+        }
+    }
+}
+
+/// Text and keyboard of the page of results of `query` starting at `offset`
+async fn pattern_page(
+    db_handler: &DatabaseHandler,
+    query: &str,
+    offset: u64,
+    me: &Me,
+) -> (String, Option<InlineKeyboardMarkup>) {
+    let Some(pattern) = Pattern::parse(query) else {
+        return (PATTERN_USAGE.to_string(), None);
+    };
+
+    let total = db_handler.count_pattern_matches(&pattern).await;
+    let lemmas: Vec<String> = db_handler
+        .get_pattern_matches(&pattern, offset, PATTERN_PAGE_SIZE)
+        .await
+        .into_iter()
+        .map(|word| word.lemma)
+        .collect();
+
+    let description = format!(
+        "<code>{}</code>{}",
+        query.split_whitespace().next().unwrap_or_default(),
+        pattern.length_description()
+    );
+
+    if lemmas.is_empty() {
+        return (format!("Ninguna palabra encaja con {}.", description), None);
+    }
+
+    let list = lemmas
+        .iter()
+        .map(|lemma| lemma_link(me.username(), lemma))
+        .collect::<Vec<String>>()
+        .join(", ");
+    let pages = total.div_ceil(PATTERN_PAGE_SIZE);
+    let mut text = format!(
+        "{} {} con {}:\n\n{}",
+        total,
+        if total == 1 {
+            "palabra encaja"
+        } else {
+            "palabras encajan"
+        },
+        description,
+        list
+    );
+    if pages > 1 {
+        text += &format!(
+            "\n\n<i>Página {} de {}</i>",
+            offset / PATTERN_PAGE_SIZE + 1,
+            pages
+        );
+    }
+
+    // The query is kept in the buttons, long ones can't be paginated
+    let page_data = |offset: u64| format!("{}{}:{}", PATTERN_CALLBACK_PREFIX, offset, query);
+    let mut navigation: Vec<InlineKeyboardButton> = vec![];
+    if offset > 0 {
+        navigation.push(InlineKeyboardButton::callback(
+            "◀",
+            page_data(offset.saturating_sub(PATTERN_PAGE_SIZE)),
+        ));
+    }
+    if offset + PATTERN_PAGE_SIZE < total {
+        navigation.push(InlineKeyboardButton::callback(
+            "▶",
+            page_data(offset + PATTERN_PAGE_SIZE),
+        ));
+    }
+    let keyboard = if navigation.is_empty()
+        || page_data(offset + PATTERN_PAGE_SIZE).len() > MAX_CALLBACK_DATA_LENGTH
+    {
+        None
+    } else {
+        Some(InlineKeyboardMarkup::new([navigation]))
+    };
+
+    (text, keyboard)
+}
+
+/// Send the first page of lemmas that match the pattern in `query`
+pub async fn send_pattern_matches(
+    db_handler: &DatabaseHandler,
+    bot: &DLEBot,
+    chat_id: ChatId,
+    query: &str,
+    me: &Me,
+) -> ResponseResult<()> {
+    let query = query.split_whitespace().collect::<Vec<&str>>().join(" ");
+    let (text, keyboard) = pattern_page(db_handler, &query, 0, me).await;
+
+    let request = bot
+        .send_message(chat_id, text)
+        .link_preview_options(DISABLED_LINK_PREVIEW);
+    match keyboard {
+        Some(keyboard) => request.reply_markup(keyboard).await?,
+        None => request.await?,
+    };
+
+    Ok(())
+}
+
+/// Edit the list of lemmas in `message_id` to show the page at `offset`
+pub async fn show_pattern_page(
+    db_handler: &DatabaseHandler,
+    bot: &DLEBot,
+    chat_id: ChatId,
+    message_id: MessageId,
+    query: &str,
+    offset: u64,
+    me: &Me,
+) -> ResponseResult<()> {
+    let (text, keyboard) = pattern_page(db_handler, query, offset, me).await;
+
+    bot.edit_message_text(chat_id, message_id, text)
+        .link_preview_options(DISABLED_LINK_PREVIEW)
+        .reply_markup(keyboard.unwrap_or_default())
+        .await?;
+
+    Ok(())
+}
+
+#[test]
+fn test_pattern_parse() {
+    assert_eq!(
+        Pattern::parse("C*ón 7"),
+        Some(Pattern {
+            like: "c%on".to_string(),
+            min_length: Some(7),
+            max_length: Some(7),
+            accent_insensitive: true,
+        })
+    );
+    assert_eq!(
+        Pattern::parse("c?s? exacto"),
+        Some(Pattern {
+            like: "c_s_".to_string(),
+            min_length: None,
+            max_length: None,
+            accent_insensitive: false,
+        })
+    );
+    assert_eq!(
+        Pattern::parse("*ción 5-"),
+        Some(Pattern {
+            like: "%cion".to_string(),
+            min_length: Some(5),
+            max_length: None,
+            accent_insensitive: true,
+        })
+    );
+    assert_eq!(Pattern::parse("*").map(|pattern| pattern.like), None);
+    assert_eq!(
+        Pattern::parse("* -8").map(|pattern| pattern.max_length),
+        Some(Some(8))
+    );
+    assert_eq!(Pattern::parse("ca%a"), None);
+    assert_eq!(Pattern::parse("c_sa"), None);
+    assert_eq!(Pattern::parse("casa 8-5"), None);
+    assert_eq!(Pattern::parse("casa siete"), None);
+    assert_eq!(Pattern::parse(""), None);
+}
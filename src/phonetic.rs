@@ -0,0 +1,180 @@
+use crate::utils::unaccent;
+
+fn is_vowel(c: char) -> bool {
+    "aeiou".contains(c)
+}
+
+/// Spanish phonetic key of `word`: words that sound the same in most varieties
+/// of Spanish get the same key. Covers the usual spelling confusions, b/v/w,
+/// c/s/z (seseo), ll/y (yeísmo), silent h, g/j before e and i, c/k/qu and
+/// doubled letters. Spaces are ignored so «a ver» and «haber» share a key.
+pub fn phonetic_key(word: &str) -> String {
+    let chars: Vec<char> = unaccent(word)
+        .chars()
+        .filter(|c| c.is_alphabetic())
+        .collect();
+    let mut key: Vec<char> = vec![];
+    let mut i = 0;
+
+    while i < chars.len() {
+        let next = chars.get(i + 1).copied();
+        let after_next = chars.get(i + 2).copied();
+
+        let mut codes: Vec<char> = match chars[i] {
+            'h' => vec![],
+            'b' | 'v' | 'w' => vec!['b'],
+            's' | 'z' => vec!['s'],
+            'c' => match next {
+                Some('h') => {
+                    i += 1;
+                    vec!['x']
+                }
+                Some('e' | 'i') => vec!['s'],
+                _ => vec!['k'],
+            },
+            'q' => {
+                if next == Some('u') {
+                    i += 1;
+                }
+                vec!['k']
+            }
+            'k' => vec!['k'],
+            'g' => match (next, after_next) {
+                (Some('e' | 'i'), _) => vec!['j'],
+                (Some('u'), Some('e' | 'i')) => {
+                    i += 1;
+                    vec!['g']
+                }
+                _ => vec!['g'],
+            },
+            'l' if next == Some('l') => {
+                i += 1;
+                vec!['y']
+            }
+            'y' => {
+                if next.is_some_and(is_vowel) {
+                    vec!['y']
+                } else {
+                    vec!['i']
+                }
+            }
+            'x' => vec!['k', 's'],
+            c => vec![c],
+        };
+
+        for code in codes.drain(..) {
+            if key.last() != Some(&code) {
+                key.push(code);
+            }
+        }
+
+        i += 1;
+    }
+
+    key.into_iter().collect()
+}
+
+/// Letters of `word` involved in each usual spelling confusion, with the note
+/// that explains it. Two homophones differ in the letters of some confusion.
+fn confusions(word: &str) -> [(String, &'static str); 7] {
+    let chars: Vec<char> = unaccent(word).chars().collect();
+    let followed_by_front_vowel = |i: usize| {
+        chars
+            .get(i + 1)
+            .is_some_and(|&next| next == 'e' || next == 'i')
+    };
+    let letters = |predicate: &dyn Fn(usize, char) -> bool| -> String {
+        chars
+            .iter()
+            .enumerate()
+            .filter(|&(i, &c)| predicate(i, c))
+            .map(|(_, &c)| c)
+            .collect()
+    };
+
+    [
+        (
+            letters(&|_, c| "bvw".contains(c)),
+            "la b y la v suenan igual",
+        ),
+        (
+            letters(&|i, c| c == 'h' && (i == 0 || chars[i - 1] != 'c')),
+            "la h no se pronuncia",
+        ),
+        (
+            letters(&|i, c| c == 's' || c == 'z' || (c == 'c' && followed_by_front_vowel(i))),
+            "la s, la z y la c ante e, i suenan igual con seseo",
+        ),
+        (
+            letters(&|i, c| c == 'y' || (c == 'l' && chars.get(i + 1) == Some(&'l'))),
+            "la ll y la y suenan igual con yeísmo",
+        ),
+        (
+            letters(&|i, c| c == 'j' || (c == 'g' && followed_by_front_vowel(i))),
+            "la g ante e, i suena como la j",
+        ),
+        (
+            letters(&|i, c| {
+                c == 'k'
+                    || c == 'q'
+                    || (c == 'c' && !followed_by_front_vowel(i) && chars.get(i + 1) != Some(&'h'))
+            }),
+            "la c, la k y la qu suenan igual",
+        ),
+        (
+            letters(&|_, c| c == ' '),
+            "se pronuncia igual aunque se escriba en una o en varias palabras",
+        ),
+    ]
+}
+
+/// Notes explaining why `word` and its homophone `lemma` sound the same
+/// although they are written differently.
+pub fn homophone_notes(word: &str, lemma: &str) -> Vec<&'static str> {
+    confusions(word)
+        .into_iter()
+        .zip(confusions(lemma))
+        .filter(|((word_letters, _), (lemma_letters, _))| word_letters != lemma_letters)
+        .map(|((_, note), _)| note)
+        .collect()
+}
+
+#[test]
+fn test_phonetic_key() {
+    assert_eq!(phonetic_key("vaca"), phonetic_key("baca"));
+    assert_eq!(phonetic_key("cayo"), phonetic_key("callo"));
+    assert_eq!(phonetic_key("haber"), phonetic_key("a ver"));
+    assert_eq!(phonetic_key("cazar"), phonetic_key("casar"));
+    assert_eq!(phonetic_key("cocer"), phonetic_key("coser"));
+    assert_eq!(phonetic_key("gira"), phonetic_key("jira"));
+    assert_eq!(phonetic_key("queso"), phonetic_key("keso"));
+    assert_eq!(phonetic_key("rey"), phonetic_key("rei"));
+    assert_ne!(phonetic_key("casa"), phonetic_key("caja"));
+    assert_ne!(phonetic_key("guerra"), phonetic_key("jerra"));
+    assert_eq!(phonetic_key("chocolate"), "xokolate");
+}
+
+#[test]
+fn test_homophone_notes() {
+    assert_eq!(
+        homophone_notes("baca", "vaca"),
+        ["la b y la v suenan igual"]
+    );
+    assert_eq!(
+        homophone_notes("cayo", "callo"),
+        ["la ll y la y suenan igual con yeísmo"]
+    );
+    assert_eq!(
+        homophone_notes("casar", "cazar"),
+        ["la s, la z y la c ante e, i suenan igual con seseo"]
+    );
+    assert_eq!(
+        homophone_notes("a ver", "haber"),
+        [
+            "la b y la v suenan igual",
+            "la h no se pronuncia",
+            "se pronuncia igual aunque se escriba en una o en varias palabras"
+        ]
+    );
+    assert!(homophone_notes("chico", "chico").is_empty());
+}
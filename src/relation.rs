@@ -0,0 +1,161 @@
+use teloxide::{prelude::*, types::Me, utils::html::escape};
+
+use crate::{
+    database::{DatabaseHandler, RelatedLemma, RelationKind},
+    utils::{lemma_link, DISABLED_LINK_PREVIEW, MAX_MASSAGE_LENGTH},
+    DLEBot,
+};
+
+/// Heading of the list of lemmas related by `kind`
+pub fn kind_title(kind: &RelationKind) -> &'static str {
+    match kind {
+        RelationKind::Synonym => "Sinónimos",
+        RelationKind::Antonym => "Antónimos",
+        RelationKind::Hypernym => "Hiperónimos",
+        RelationKind::Related => "Relacionadas",
+    }
+}
+
+/// Group `relations`, already sorted by kind, into one list per kind
+fn group_relations(relations: &[RelatedLemma]) -> Vec<(&RelationKind, Vec<&str>)> {
+    let mut groups: Vec<(&RelationKind, Vec<&str>)> = vec![];
+
+    for relation in relations {
+        match groups.last_mut() {
+            Some((kind, lemmas)) if **kind == relation.kind => lemmas.push(&relation.related),
+            _ => groups.push((&relation.kind, vec![&relation.related])),
+        }
+    }
+
+    groups
+}
+
+/// Messages of at most `max_length` characters with `header` and then each
+/// group as its title followed by its items separated by commas. Groups are
+/// only split between items, repeating the title, so no link is cut.
+fn pack_groups(header: &str, groups: &[(&str, Vec<String>)], max_length: usize) -> Vec<String> {
+    let mut messages: Vec<String> = vec![];
+    let mut current = header.to_string();
+
+    for (title, items) in groups {
+        for (index, item) in items.iter().enumerate() {
+            let piece = if index == 0 {
+                format!("\n\n<b>{}</b>: {}", title, item)
+            } else {
+                format!(", {}", item)
+            };
+
+            if current.chars().count() + piece.chars().count() > max_length {
+                messages.push(current);
+                current = format!("<b>{}</b>: {}", title, item);
+            } else {
+                current += &piece;
+            }
+        }
+    }
+    messages.push(current);
+
+    messages
+}
+
+/// Send the synonyms, antonyms and related words of `lemma`, each one linked
+/// to its definition
+pub async fn send_relations(
+    db_handler: &DatabaseHandler,
+    bot: &DLEBot,
+    chat_id: ChatId,
+    lemma: &str,
+    me: &Me,
+) -> ResponseResult<()> {
+    let lemma = lemma.trim();
+
+    if lemma.is_empty() {
+        bot.send_message(
+            chat_id,
+            "Escribe la palabra después del comando, por ejemplo: <code>/sinonimos casa</code>",
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let relations = db_handler.get_relations(lemma).await;
+
+    let messages = if relations.is_empty() {
+        vec![format!(
+            "No conozco sinónimos ni antónimos de «{}».",
+            escape(lemma)
+        )]
+    } else {
+        let groups: Vec<(&str, Vec<String>)> = group_relations(&relations)
+            .into_iter()
+            .map(|(kind, lemmas)| {
+                (
+                    kind_title(kind),
+                    lemmas
+                        .iter()
+                        .map(|related| lemma_link(me.username(), related))
+                        .collect(),
+                )
+            })
+            .collect();
+        pack_groups(
+            &format!("Palabras relacionadas con «{}»:", escape(lemma)),
+            &groups,
+            MAX_MASSAGE_LENGTH,
+        )
+    };
+
+    for text in messages {
+        bot.send_message(chat_id, text)
+            .link_preview_options(DISABLED_LINK_PREVIEW)
+            .await?;
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_group_relations() {
+    let relations: Vec<RelatedLemma> = [
+        ("hogar", RelationKind::Synonym),
+        ("morada", RelationKind::Synonym),
+        ("edificio", RelationKind::Hypernym),
+        ("casero", RelationKind::Related),
+    ]
+    .into_iter()
+    .map(|(related, kind)| RelatedLemma {
+        related: related.to_string(),
+        kind,
+    })
+    .collect();
+
+    assert_eq!(
+        group_relations(&relations),
+        [
+            (&RelationKind::Synonym, vec!["hogar", "morada"]),
+            (&RelationKind::Hypernym, vec!["edificio"]),
+            (&RelationKind::Related, vec!["casero"]),
+        ]
+    );
+}
+
+#[test]
+fn test_pack_groups() {
+    let groups = [
+        ("Sinónimos", vec!["hogar".to_string(), "morada".to_string()]),
+        ("Antónimos", vec!["calle".to_string()]),
+    ];
+
+    assert_eq!(
+        pack_groups("Casa:", &groups, 100),
+        ["Casa:\n\n<b>Sinónimos</b>: hogar, morada\n\n<b>Antónimos</b>: calle"]
+    );
+    assert_eq!(
+        pack_groups("Casa:", &groups, 30),
+        [
+            "Casa:\n\n<b>Sinónimos</b>: hogar",
+            "<b>Sinónimos</b>: morada",
+            "<b>Antónimos</b>: calle"
+        ]
+    );
+}
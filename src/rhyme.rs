@@ -0,0 +1,224 @@
+use teloxide::{prelude::*, types::Me, utils::html::escape};
+
+use crate::{
+    database::DatabaseHandler,
+    syllable::Syllabification,
+    utils::{lemma_link, unaccent, DISABLED_LINK_PREVIEW},
+    DLEBot,
+};
+
+/// Maximum number of lemmas listed by `/anagramas` and in each kind of rhyme
+const WORD_GAME_LIMIT: u64 = 40;
+
+/// Sorted letters of `word` ignoring case, written accents and anything that
+/// isn't a letter, so every anagram of a word shares it
+pub fn anagram_key(word: &str) -> String {
+    let mut letters: Vec<char> = unaccent(word)
+        .chars()
+        .filter(|c| c.is_alphabetic())
+        .collect();
+    letters.sort_unstable();
+
+    letters.into_iter().collect()
+}
+
+/// Byte index in `syllable` of the vowel that carries its sound: the strong
+/// or accented one, or the last one if all are weak like in «cui» or «gui»
+fn main_vowel(syllable: &str) -> Option<usize> {
+    let vowels: Vec<(usize, char)> = syllable
+        .char_indices()
+        .filter(|&(_, c)| "aeiouáéíóúü".contains(c))
+        .collect();
+
+    vowels
+        .iter()
+        .find(|&&(_, c)| "aeoáéóíú".contains(c))
+        .or(vowels.last())
+        .map(|&(index, _)| index)
+}
+
+/// Syllables of the last word of `word` and the index of the stressed one
+fn stressed_syllables(word: &str) -> Option<Syllabification> {
+    Syllabification::new(word.split_whitespace().last()?)
+}
+
+/// Consonant rhyme key of `word`: everything from its stressed vowel on,
+/// without written accents. «canción» and «camión» share «on».
+pub fn consonant_rhyme(word: &str) -> Option<String> {
+    let Syllabification { syllables, tonic } = stressed_syllables(word)?;
+    let start = main_vowel(&syllables[tonic])?;
+
+    Some(unaccent(
+        &(syllables[tonic][start..].to_string() + &syllables[tonic + 1..].concat()),
+    ))
+}
+
+/// Assonant rhyme key of `word`: its stressed vowel and, if it isn't the
+/// last syllable, the vowel of the last one. As in traditional metrics the
+/// syllables in between don't count, so «cántaro» and «pato» share «ao».
+pub fn assonant_rhyme(word: &str) -> Option<String> {
+    let Syllabification { syllables, tonic } = stressed_syllables(word)?;
+    let vowel =
+        |syllable: &str| -> Option<char> { syllable[main_vowel(syllable)?..].chars().next() };
+
+    let mut key = String::from(vowel(&syllables[tonic])?);
+    if tonic + 1 < syllables.len() {
+        key.push(vowel(syllables.last()?)?);
+    }
+
+    Some(unaccent(&key))
+}
+
+fn links(lemmas: &[String], me: &Me) -> String {
+    lemmas
+        .iter()
+        .map(|lemma| lemma_link(me.username(), lemma))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+/// Send the lemmas that are anagrams of `word`
+pub async fn send_anagrams(
+    db_handler: &DatabaseHandler,
+    bot: &DLEBot,
+    chat_id: ChatId,
+    word: &str,
+    me: &Me,
+) -> ResponseResult<()> {
+    let word = word.trim();
+
+    if word.is_empty() {
+        bot.send_message(
+            chat_id,
+            "Escribe la palabra después del comando, por ejemplo: <code>/anagramas amor</code>",
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let anagrams = db_handler.get_anagrams(word, WORD_GAME_LIMIT).await;
+
+    let text = if anagrams.is_empty() {
+        format!("No encontré anagramas de «{}».", escape(word))
+    } else {
+        format!(
+            "Anagramas de «{}»:\n\n{}",
+            escape(word),
+            links(&anagrams, me)
+        )
+    };
+    bot.send_message(chat_id, text)
+        .link_preview_options(DISABLED_LINK_PREVIEW)
+        .await?;
+
+    Ok(())
+}
+
+/// Send the lemmas that rhyme with `word`, first in consonant rhyme and then
+/// those that only rhyme in assonant rhyme
+pub async fn send_rhymes(
+    db_handler: &DatabaseHandler,
+    bot: &DLEBot,
+    chat_id: ChatId,
+    word: &str,
+    me: &Me,
+) -> ResponseResult<()> {
+    let word = word.trim();
+
+    if word.is_empty() {
+        bot.send_message(
+            chat_id,
+            "Escribe la palabra después del comando, por ejemplo: <code>/rima canción</code>",
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let (Some(consonant), Some(assonant)) = (consonant_rhyme(word), assonant_rhyme(word)) else {
+        bot.send_message(
+            chat_id,
+            format!("No sé dónde lleva el acento «{}».", escape(word)),
+        )
+        .await?;
+        return Ok(());
+    };
+
+    let consonant_rhymes = db_handler.get_consonant_rhymes(word, WORD_GAME_LIMIT).await;
+    let assonant_rhymes = db_handler.get_assonant_rhymes(word, WORD_GAME_LIMIT).await;
+
+    let text = if consonant_rhymes.is_empty() && assonant_rhymes.is_empty() {
+        format!("No encontré rimas para «{}».", escape(word))
+    } else {
+        let mut text = format!("Rimas de «{}»:", escape(word));
+        if !consonant_rhymes.is_empty() {
+            text += &format!(
+                "\n\n<b>Consonantes</b> (-{}): {}",
+                consonant,
+                links(&consonant_rhymes, me)
+            );
+        }
+        if !assonant_rhymes.is_empty() {
+            text += &format!(
+                "\n\n<b>Asonantes</b> ({}): {}",
+                assonant
+                    .chars()
+                    .map(String::from)
+                    .collect::<Vec<String>>()
+                    .join("-"),
+                links(&assonant_rhymes, me)
+            );
+        }
+        text
+    };
+    bot.send_message(chat_id, text)
+        .link_preview_options(DISABLED_LINK_PREVIEW)
+        .await?;
+
+    Ok(())
+}
+
+#[test]
+fn test_anagram_key() {
+    assert_eq!(anagram_key("Roma"), "amor");
+    assert_eq!(anagram_key("ramo"), anagram_key("mora"));
+    assert_eq!(anagram_key("caña"), "aacñ");
+    assert_eq!(anagram_key("ánimo"), anagram_key("mano i"));
+}
+
+#[test]
+fn test_rhymes() {
+    let cases = [
+        ("canción", "on", "o"),
+        ("camión", "on", "o"),
+        ("amor", "or", "o"),
+        ("sol", "ol", "o"),
+        ("cielo", "elo", "eo"),
+        ("patria", "atria", "aa"),
+        ("cántaro", "antaro", "ao"),
+        ("tranvía", "ia", "ia"),
+        ("queso", "eso", "eo"),
+        ("guiso", "iso", "io"),
+        ("pingüino", "ino", "io"),
+        ("reloj", "oj", "o"),
+        ("virrey", "ey", "e"),
+        ("examen", "amen", "ae"),
+        ("ciudad", "ad", "a"),
+        ("cuida", "ida", "ia"),
+        ("poeta", "eta", "ea"),
+        ("agua", "agua", "aa"),
+        ("a bote pronto", "onto", "oo"),
+    ];
+
+    for (word, consonant, assonant) in cases {
+        assert_eq!(
+            consonant_rhyme(word).as_deref(),
+            Some(consonant),
+            "{}",
+            word
+        );
+        assert_eq!(assonant_rhyme(word).as_deref(), Some(assonant), "{}", word);
+    }
+
+    assert_eq!(consonant_rhyme("pst"), None);
+    assert_eq!(consonant_rhyme("anti-"), None);
+}
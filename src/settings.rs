@@ -0,0 +1,321 @@
+use chrono::{NaiveTime, Timelike};
+use teloxide::{
+    prelude::*,
+    types::{InlineKeyboardButton, InlineKeyboardMarkup},
+    ApiError, RequestError,
+};
+
+use crate::{
+    database::{DatabaseHandler, OutputFormat},
+    utils::{DESUBS_CALLBACK_DATA, SETTINGS_CALLBACK_PREFIX, SUBS_CALLBACK_DATA},
+    DLEBot,
+};
+
+/// Sections of the settings menu, the data of their buttons is the section
+/// optionally followed by `:` and the value to set in it
+pub const MENU: &str = "menu";
+pub const SUBSCRIPTION: &str = "sub";
+const DELIVERY: &str = "time";
+const FORMAT: &str = "fmt";
+const INLINE: &str = "inline";
+/// Value of `MENU` that switches the syllables at the end of definitions
+const SYLLABLES: &str = "syl";
+/// Value of `INLINE` that searches in every dictionary
+const ALL_DICTIONARIES: &str = "*";
+/// Hours offered in the delivery section, any other is set with /hora
+const DELIVERY_HOURS: [u32; 8] = [7, 8, 9, 10, 12, 15, 18, 21];
+
+const OUTPUT_FORMATS: [(OutputFormat, &str, &str); 3] = [
+    (OutputFormat::Full, "full", "Completo"),
+    (OutputFormat::Compact, "compact", "Compacto"),
+    (OutputFormat::Image, "image", "Imagen"),
+];
+
+fn button(text: &str, section: &str, value: Option<&str>) -> InlineKeyboardButton {
+    let data = match value {
+        Some(value) => format!("{}{}:{}", SETTINGS_CALLBACK_PREFIX, section, value),
+        None => format!("{}{}", SETTINGS_CALLBACK_PREFIX, section),
+    };
+
+    InlineKeyboardButton::callback(text, data)
+}
+
+fn checked(text: &str, selected: bool) -> String {
+    if selected {
+        format!("✅ {}", text)
+    } else {
+        text.to_string()
+    }
+}
+
+fn yes_no(value: bool) -> &'static str {
+    if value {
+        "sí"
+    } else {
+        "no"
+    }
+}
+
+fn back_row() -> Vec<InlineKeyboardButton> {
+    vec![button("◀ Volver", MENU, None)]
+}
+
+/// Text and buttons of the section `section` of the settings of the user
+async fn settings_view(
+    db_handler: &DatabaseHandler,
+    user_id: i64,
+    first_name: &str,
+    section: &str,
+) -> (String, InlineKeyboardMarkup) {
+    let settings = db_handler.get_user(user_id).await;
+    let subscribed = settings
+        .as_ref()
+        .is_some_and(|settings| settings.subscribed);
+    let output_format = db_handler.get_output_format(user_id).await;
+    let (time, timezone) = db_handler.get_delivery_settings(user_id).await;
+    let inline_dictionary = db_handler.get_inline_dictionary(user_id).await;
+    let dictionaries = db_handler.get_dictionaries().await;
+    let inline_name = inline_dictionary
+        .as_ref()
+        .and_then(|code| {
+            dictionaries
+                .iter()
+                .find(|dictionary| &dictionary.code == code)
+        })
+        .map_or("todos".to_string(), |dictionary| {
+            dictionary.short_name.clone()
+        });
+    let delivery = format!("{} ({})", time.format("%H:%M"), timezone);
+
+    match section {
+        SUBSCRIPTION => {
+            let text = format!(
+                include_str!("templates/subscription.txt"),
+                first_name,
+                if subscribed { "SÍ" } else { "NO" }
+            );
+            let (label, data) = if subscribed {
+                ("Desuscribirme", DESUBS_CALLBACK_DATA)
+            } else {
+                ("¡Suscribirme!", SUBS_CALLBACK_DATA)
+            };
+
+            (
+                format!("{}\n\nLa recibirás cada día a las {}.", text, delivery),
+                InlineKeyboardMarkup::new([
+                    vec![
+                        InlineKeyboardButton::callback(label, data),
+                        button("⏰ Cambiar hora", DELIVERY, None),
+                    ],
+                    back_row(),
+                ]),
+            )
+        }
+        DELIVERY => {
+            let hours: Vec<InlineKeyboardButton> = DELIVERY_HOURS
+                .iter()
+                .map(|hour| {
+                    button(
+                        &checked(
+                            &format!("{}:00", hour),
+                            time.hour() == *hour && time.minute() == 0,
+                        ),
+                        DELIVERY,
+                        Some(&hour.to_string()),
+                    )
+                })
+                .collect();
+
+            (
+                format!(
+                    "⏰ <b>Hora de la palabra del día</b>\n\nAhora la recibes a las {}. Elige otra hora, o usa /hora para cualquier otra o para cambiar la zona horaria, por ejemplo: <code>/hora 8:30 America/Havana</code>",
+                    delivery
+                ),
+                InlineKeyboardMarkup::new(hours.chunks(4).map(|row| row.to_vec()))
+                    .append_row(back_row()),
+            )
+        }
+        FORMAT => {
+            let buttons: Vec<InlineKeyboardButton> = OUTPUT_FORMATS
+                .iter()
+                .map(|(format, value, name)| {
+                    button(
+                        &checked(name, *format == output_format),
+                        FORMAT,
+                        Some(value),
+                    )
+                })
+                .collect();
+
+            (
+                "📝 <b>Formato de las definiciones</b>\n\n<b>Completo</b>: la definición entera.\n<b>Compacto</b>: una acepción cada vez, con botones para pasar a las demás.\n<b>Imagen</b>: como la palabra del día, si la definición es corta.".to_string(),
+                InlineKeyboardMarkup::new([buttons, back_row()]),
+            )
+        }
+        INLINE => {
+            let mut buttons = vec![button(
+                &checked("Todos", inline_dictionary.is_none()),
+                INLINE,
+                Some(ALL_DICTIONARIES),
+            )];
+            buttons.extend(dictionaries.iter().map(|dictionary| {
+                button(
+                    &checked(
+                        &dictionary.short_name,
+                        inline_dictionary.as_ref() == Some(&dictionary.code),
+                    ),
+                    INLINE,
+                    Some(&dictionary.code),
+                )
+            }));
+
+            (
+                "🔎 <b>Modo inline</b>\n\nElige en qué diccionario buscar cuando escribes el nombre del bot seguido de una palabra en cualquier chat. Aún puedes buscar en otro escribiendo su código, por ejemplo <code>dpd:palabra</code>.".to_string(),
+                InlineKeyboardMarkup::new(buttons.chunks(3).map(|row| row.to_vec()))
+                    .append_row(back_row()),
+            )
+        }
+        _ => {
+            let show_syllables = db_handler.get_show_syllables(user_id).await;
+            let format_name = OUTPUT_FORMATS
+                .iter()
+                .find(|(format, _, _)| *format == output_format)
+                .map_or("", |(_, _, name)| name);
+
+            (
+                format!(
+                    "⚙️ <b>Ajustes</b>\n\n📖 Palabra del día: {}\n⏰ Hora: {}\n📝 Formato: {}\n🔤 Sílabas al final: {}\n🔎 Diccionario del modo inline: {}",
+                    yes_no(subscribed),
+                    delivery,
+                    format_name,
+                    yes_no(show_syllables),
+                    inline_name
+                ),
+                InlineKeyboardMarkup::new([
+                    vec![
+                        button("📖 Palabra del día", SUBSCRIPTION, None),
+                        button("⏰ Hora", DELIVERY, None),
+                    ],
+                    vec![
+                        button("📝 Formato", FORMAT, None),
+                        button("🔎 Modo inline", INLINE, None),
+                    ],
+                    vec![button(
+                        &format!("🔤 Sílabas: {}", yes_no(show_syllables)),
+                        MENU,
+                        Some(SYLLABLES),
+                    )],
+                ]),
+            )
+        }
+    }
+}
+
+/// Send the section `section` of the settings of the sender of `msg`
+pub async fn send_settings(
+    db_handler: &DatabaseHandler,
+    bot: &DLEBot,
+    msg: &Message,
+    user_id: i64,
+    section: &str,
+) -> ResponseResult<()> {
+    let Some(user) = msg.from.as_ref().filter(|_| msg.chat.is_private()) else {
+        bot.send_message(
+            msg.chat.id,
+            "Tus ajustes se cambian en un chat privado conmigo.",
+        )
+        .await?;
+        return Ok(());
+    };
+
+    let (text, keyboard) = settings_view(db_handler, user_id, &user.first_name, section).await;
+    bot.send_message(msg.chat.id, text)
+        .reply_markup(keyboard)
+        .await?;
+
+    Ok(())
+}
+
+/// Replace the message of `query` with the section `section` of the
+/// settings of the user that pressed the button
+pub async fn edit_settings(
+    db_handler: &DatabaseHandler,
+    bot: &DLEBot,
+    query: &CallbackQuery,
+    user_id: i64,
+    section: &str,
+) -> ResponseResult<()> {
+    let Some(message) = &query.message else {
+        return Ok(());
+    };
+    let (text, keyboard) =
+        settings_view(db_handler, user_id, &query.from.first_name, section).await;
+
+    match bot
+        .edit_message_text(message.chat().id, message.id(), text)
+        .reply_markup(keyboard)
+        .await
+    {
+        // The button of the current value was pressed
+        Err(RequestError::Api(ApiError::MessageNotModified)) => Ok(()),
+        result => result.map(|_| ()),
+    }
+}
+
+/// Apply the value in `data`, if any, and show the section it names
+pub async fn handle_settings_callback(
+    db_handler: &DatabaseHandler,
+    bot: &DLEBot,
+    query: &CallbackQuery,
+    user_id: i64,
+    data: &str,
+) -> ResponseResult<()> {
+    let (section, value) = match data.split_once(':') {
+        Some((section, value)) => (section, Some(value)),
+        None => (data, None),
+    };
+
+    match (section, value) {
+        (MENU, Some(SYLLABLES)) => {
+            let show_syllables = !db_handler.get_show_syllables(user_id).await;
+            db_handler.set_show_syllables(user_id, show_syllables).await;
+        }
+        (DELIVERY, Some(hour)) => {
+            if let Some(time) = hour
+                .parse()
+                .ok()
+                .and_then(|hour| NaiveTime::from_hms_opt(hour, 0, 0))
+            {
+                let (_, timezone) = db_handler.get_delivery_settings(user_id).await;
+                db_handler
+                    .set_delivery_settings(user_id, time, &timezone)
+                    .await;
+            }
+        }
+        (FORMAT, Some(value)) => {
+            if let Some((format, _, _)) = OUTPUT_FORMATS
+                .iter()
+                .find(|(_, candidate, _)| *candidate == value)
+            {
+                db_handler.set_output_format(user_id, format.clone()).await;
+            }
+        }
+        (INLINE, Some(ALL_DICTIONARIES)) => {
+            db_handler.set_inline_dictionary(user_id, None).await;
+        }
+        (INLINE, Some(code)) => {
+            let dictionaries = db_handler.get_dictionaries().await;
+            if dictionaries
+                .iter()
+                .any(|dictionary| dictionary.code == code)
+            {
+                db_handler
+                    .set_inline_dictionary(user_id, Some(code.to_string()))
+                    .await;
+            }
+        }
+        _ => {}
+    }
+
+    edit_settings(db_handler, bot, query, user_id, section).await
+}
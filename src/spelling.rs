@@ -0,0 +1,216 @@
+use teloxide::{prelude::*, types::Me, utils::html::escape};
+
+use crate::{
+    database::DatabaseHandler,
+    utils::{lemma_link, DISABLED_LINK_PREVIEW},
+    DLEBot,
+};
+
+/// Maximum number of characters of a text to check
+const MAX_TEXT_LENGTH: usize = 1000;
+/// Maximum number of suspected misspellings with suggestions
+const MAX_MISSPELLINGS: usize = 15;
+/// Number of suggestions for each suspected misspelling
+const SPELLING_SUGGESTIONS: usize = 3;
+/// Forms not in the dictionary nor generated by the inflection index
+const KNOWN_FORMS: [&str; 8] = ["los", "las", "unos", "unas", "les", "mis", "tus", "sus"];
+
+/// Piece of a text, either a word or what is between two words
+#[derive(Debug, PartialEq)]
+pub struct Token<'a> {
+    pub text: &'a str,
+    pub word: bool,
+    /// Whether it's the first word of a sentence
+    sentence_start: bool,
+}
+
+/// Split `text` in words and the text between them. Words are runs of
+/// letters, so numbers and punctuation are left out.
+pub fn tokenize(text: &str) -> Vec<Token<'_>> {
+    let mut tokens: Vec<Token> = vec![];
+    let mut start = 0;
+    let mut sentence_start = true;
+
+    // Whether each char is a letter, and `None` after the end of the text
+    let kinds = text
+        .char_indices()
+        .map(|(index, c)| (index, Some(c.is_alphabetic())))
+        .chain(std::iter::once((text.len(), None)));
+    let mut current: Option<bool> = None;
+
+    for (index, kind) in kinds {
+        if kind == current {
+            continue;
+        }
+        if let Some(word) = current {
+            let piece = &text[start..index];
+            tokens.push(Token {
+                text: piece,
+                word,
+                sentence_start: word && sentence_start,
+            });
+            if word {
+                sentence_start = false;
+            } else if piece.contains(['.', '?', '!', '¿', '¡', '\n']) {
+                sentence_start = true;
+            }
+        }
+        start = index;
+        current = kind;
+    }
+
+    tokens
+}
+
+/// Words of `tokens` that have to be checked, in lowercase. Capitalized
+/// words in the middle of a sentence are taken as proper names.
+fn words_to_check(tokens: &[Token]) -> Vec<String> {
+    let mut words: Vec<String> = tokens
+        .iter()
+        .filter(|token| token.word)
+        .filter(|token| {
+            token.sentence_start || !token.text.chars().next().is_some_and(char::is_uppercase)
+        })
+        .map(|token| token.text.to_lowercase())
+        .filter(|word| !KNOWN_FORMS.contains(&word.as_str()))
+        .collect();
+    words.sort();
+    words.dedup();
+
+    words
+}
+
+/// HTML of `tokens` with the words in `misspellings` underlined
+fn annotate(tokens: &[Token], misspellings: &[String]) -> String {
+    tokens
+        .iter()
+        .map(|token| {
+            if token.word && misspellings.contains(&token.text.to_lowercase()) {
+                format!("<u>{}</u>", escape(token.text))
+            } else {
+                escape(token.text)
+            }
+        })
+        .collect()
+}
+
+/// Check every word of `text` against the lemmas and their inflected forms,
+/// and send the text with the unknown words underlined and suggestions for
+/// each of them
+pub async fn send_spelling_check(
+    db_handler: &DatabaseHandler,
+    bot: &DLEBot,
+    chat_id: ChatId,
+    text: &str,
+    me: &Me,
+) -> ResponseResult<()> {
+    let text = text.trim();
+
+    if text.is_empty() {
+        bot.send_message(
+            chat_id,
+            "Escribe el texto después del comando, por ejemplo: <code>/corregir Ayer fui a la vivlioteca</code>",
+        )
+        .await?;
+        return Ok(());
+    }
+    if text.chars().count() > MAX_TEXT_LENGTH {
+        bot.send_message(
+            chat_id,
+            format!(
+                "El texto es demasiado largo, puedo revisar hasta {} caracteres.",
+                MAX_TEXT_LENGTH
+            ),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let tokens = tokenize(text);
+    let words = words_to_check(&tokens);
+    let known = db_handler.filter_known_words(&words).await;
+    let misspellings: Vec<String> = words
+        .into_iter()
+        .filter(|word| !known.contains(word))
+        .collect();
+
+    if misspellings.is_empty() {
+        bot.send_message(chat_id, "✅ No encontré errores en el texto.")
+            .await?;
+        return Ok(());
+    }
+
+    let mut suggestions: Vec<String> = vec![];
+    for word in misspellings.iter().take(MAX_MISSPELLINGS) {
+        let similar = db_handler
+            .get_fuzzy_list(word, SPELLING_SUGGESTIONS)
+            .await
+            .iter()
+            .map(|lemma| lemma_link(me.username(), lemma))
+            .collect::<Vec<String>>();
+        suggestions.push(if similar.is_empty() {
+            format!("<b>{}</b>: sin sugerencias", escape(word))
+        } else {
+            format!("<b>{}</b> → {}", escape(word), similar.join(", "))
+        });
+    }
+
+    bot.send_message(
+        chat_id,
+        format!(
+            "{}\n\nNo encontré en el diccionario las palabras subrayadas:\n\n{}",
+            annotate(&tokens, &misspellings),
+            suggestions.join("\n")
+        ),
+    )
+    .link_preview_options(DISABLED_LINK_PREVIEW)
+    .await?;
+
+    Ok(())
+}
+
+#[test]
+fn test_tokenize() {
+    let tokens = tokenize("¿Vino Pedro? Sí, a las 5.");
+    let words: Vec<(&str, bool)> = tokens
+        .iter()
+        .filter(|token| token.word)
+        .map(|token| (token.text, token.sentence_start))
+        .collect();
+
+    assert_eq!(
+        words,
+        [
+            ("Vino", true),
+            ("Pedro", false),
+            ("Sí", true),
+            ("a", false),
+            ("las", false)
+        ]
+    );
+    assert_eq!(
+        tokens.iter().map(|token| token.text).collect::<String>(),
+        "¿Vino Pedro? Sí, a las 5."
+    );
+    assert_eq!(tokenize(""), []);
+}
+
+#[test]
+fn test_words_to_check() {
+    let tokens = tokenize("Ayer vi a Pedro en la vivlioteca. Ayer, no hoy.");
+
+    assert_eq!(
+        words_to_check(&tokens),
+        ["a", "ayer", "en", "hoy", "la", "no", "vi", "vivlioteca"]
+    );
+}
+
+#[test]
+fn test_annotate() {
+    let tokens = tokenize("Fui a la vivlioteca <ya>");
+
+    assert_eq!(
+        annotate(&tokens, &["vivlioteca".to_string()]),
+        "Fui a la <u>vivlioteca</u> &lt;ya&gt;"
+    );
+}
@@ -0,0 +1,511 @@
+use std::fmt;
+
+use teloxide::{prelude::*, utils::html::escape};
+
+use crate::DLEBot;
+
+const STRONG_VOWELS: &str = "aeoáéó";
+const ACCENTED_VOWELS: &str = "áéíóú";
+const VOWELS: &str = "aeiouáéíóúü";
+
+fn is_strong(c: char) -> bool {
+    STRONG_VOWELS.contains(c)
+}
+
+/// Position of the stressed syllable counted from the end of the word
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stress {
+    /// Last syllable
+    Aguda,
+    /// Second to last syllable
+    Llana,
+    /// Third to last syllable
+    Esdrujula,
+    /// Any syllable before the third to last
+    Sobresdrujula,
+}
+
+impl fmt::Display for Stress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Stress::Aguda => "aguda",
+            Stress::Llana => "llana",
+            Stress::Esdrujula => "esdrújula",
+            Stress::Sobresdrujula => "sobresdrújula",
+        })
+    }
+}
+
+/// Letter or group of letters that makes a single sound for the division in
+/// syllables: «ch», «ll», «rr», and «qu» and «gu» before «e» or «i» are one
+/// consonant. «y» is a vowel when no vowel follows it, like in «rey».
+#[derive(Debug)]
+struct Unit {
+    text: String,
+    vowel: bool,
+}
+
+fn units(word: &str) -> Option<Vec<Unit>> {
+    let chars: Vec<char> = word.chars().collect();
+    let mut units: Vec<Unit> = vec![];
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied().unwrap_or(' ');
+        let after_next = chars.get(i + 2).copied().unwrap_or(' ');
+
+        if !c.is_alphabetic() {
+            return None;
+        }
+
+        let length = match (c, next) {
+            ('c', 'h') | ('l', 'l') | ('r', 'r') => 2,
+            ('q' | 'g', 'u') if "eiéí".contains(after_next) => 2,
+            _ => 1,
+        };
+        units.push(Unit {
+            text: chars[i..i + length].iter().collect(),
+            vowel: length == 1 && (VOWELS.contains(c) || (c == 'y' && !VOWELS.contains(next))),
+        });
+        i += length;
+    }
+
+    Some(units)
+}
+
+/// Whether two vowels next to each other belong to different syllables: two
+/// strong vowels, a weak vowel with written accent next to a strong one, or
+/// the same weak vowel twice like in «chiita»
+fn is_hiatus(previous: char, current: char) -> bool {
+    (is_strong(previous) && is_strong(current))
+        || (is_strong(previous) && "íú".contains(current))
+        || ("íú".contains(previous) && is_strong(current))
+        || (previous == current && "iu".contains(current))
+}
+
+/// Consonant groups that start a syllable together, like «pr» or «bl»
+fn is_inseparable(first: &str, second: &str) -> bool {
+    matches!(
+        (first, second),
+        ("b" | "c" | "f" | "g" | "k" | "p", "l" | "r") | ("d" | "t", "r")
+    )
+}
+
+/// Number of the consonants between two nuclei that stay with the first one
+fn coda_length(consonants: &[&str]) -> usize {
+    let n = consonants.len();
+    match n {
+        0 | 1 => 0,
+        _ if is_inseparable(consonants[n - 2], consonants[n - 1]) => n - 2,
+        _ => n - 1,
+    }
+}
+
+/// Split a single word in syllables. Returns `None` if it has something
+/// other than letters or no vowels.
+pub fn syllabify(word: &str) -> Option<Vec<String>> {
+    let word = word.to_lowercase();
+    let units = units(&word)?;
+
+    // Ranges of units of each syllable nucleus
+    let mut nuclei: Vec<(usize, usize)> = vec![];
+    for (i, unit) in units.iter().enumerate() {
+        if !unit.vowel {
+            continue;
+        }
+        let current = unit.text.chars().next()?;
+        match nuclei.last_mut() {
+            Some((_, end))
+                if *end == i && !is_hiatus(units[i - 1].text.chars().next()?, current) =>
+            {
+                *end = i + 1
+            }
+            _ => nuclei.push((i, i + 1)),
+        }
+    }
+    if nuclei.is_empty() {
+        return None;
+    }
+
+    let mut boundaries: Vec<usize> = vec![0];
+    for pair in nuclei.windows(2) {
+        let (_, end) = pair[0];
+        let (start, _) = pair[1];
+        let consonants: Vec<&str> = units[end..start]
+            .iter()
+            .map(|unit| unit.text.as_str())
+            .collect();
+        boundaries.push(end + coda_length(&consonants));
+    }
+    boundaries.push(units.len());
+
+    Some(
+        boundaries
+            .windows(2)
+            .map(|range| {
+                units[range[0]..range[1]]
+                    .iter()
+                    .map(|unit| unit.text.as_str())
+                    .collect()
+            })
+            .collect(),
+    )
+}
+
+/// Division in syllables and stress of a word, as written
+#[derive(Debug, PartialEq)]
+pub struct Syllabification {
+    pub syllables: Vec<String>,
+    /// Index in `syllables` of the stressed one
+    pub tonic: usize,
+}
+
+impl Syllabification {
+    /// Analyze a single word. Without written accent the stress follows the
+    /// rules: words ending in vowel, or in «n» or «s» after a vowel, are
+    /// llanas and the rest agudas.
+    pub fn new(word: &str) -> Option<Syllabification> {
+        let syllables = syllabify(word)?;
+
+        let tonic = match syllables
+            .iter()
+            .position(|syllable| syllable.chars().any(|c| ACCENTED_VOWELS.contains(c)))
+        {
+            Some(accented) => accented,
+            None if ends_like_llana(&syllables) => syllables.len().saturating_sub(2),
+            None => syllables.len() - 1,
+        };
+
+        Some(Syllabification { syllables, tonic })
+    }
+
+    pub fn stress(&self) -> Stress {
+        match self.syllables.len() - 1 - self.tonic {
+            0 => Stress::Aguda,
+            1 => Stress::Llana,
+            2 => Stress::Esdrujula,
+            _ => Stress::Sobresdrujula,
+        }
+    }
+
+    pub fn has_written_accent(&self) -> bool {
+        self.syllables
+            .iter()
+            .any(|syllable| syllable.chars().any(|c| ACCENTED_VOWELS.contains(c)))
+    }
+
+    /// Whether the stressed vowel is a weak vowel with written accent next to
+    /// a strong one, like in «río» or «búho»
+    fn is_accented_hiatus(&self) -> bool {
+        let tonic = &self.syllables[self.tonic];
+        let Some(weak) = tonic.chars().position(|c| "íú".contains(c)) else {
+            return false;
+        };
+
+        let before = if weak == 0 {
+            self.tonic
+                .checked_sub(1)
+                .and_then(|previous| self.syllables[previous].chars().last())
+        } else {
+            tonic.chars().nth(weak - 1)
+        };
+        let after = match tonic.chars().nth(weak + 1) {
+            Some(c) => Some(c),
+            // «h» doesn't prevent the hiatus
+            None => self
+                .syllables
+                .get(self.tonic + 1)
+                .and_then(|next| next.trim_start_matches('h').chars().next()),
+        };
+
+        before.is_some_and(is_strong) || after.is_some_and(is_strong)
+    }
+
+    /// Whether the general rules of accentuation require a written accent
+    pub fn needs_written_accent(&self) -> bool {
+        if self.syllables.len() == 1 {
+            return false;
+        }
+        if self.is_accented_hiatus() {
+            return true;
+        }
+
+        match self.stress() {
+            Stress::Aguda => ends_like_llana(&self.syllables),
+            Stress::Llana => !ends_like_llana(&self.syllables),
+            Stress::Esdrujula | Stress::Sobresdrujula => true,
+        }
+    }
+
+    /// Explanation of why the word carries a written accent or not
+    pub fn accent_rule(&self) -> String {
+        let last = self
+            .syllables
+            .last()
+            .and_then(|syllable| syllable.chars().last())
+            .unwrap_or_default();
+
+        if self.syllables.len() == 1 {
+            return if self.has_written_accent() {
+                "Es monosílaba y lleva tilde diacrítica.".to_string()
+            } else {
+                "Es monosílaba, no lleva tilde salvo la diacrítica.".to_string()
+            };
+        }
+        if self.is_accented_hiatus() {
+            return "Lleva tilde para marcar el hiato.".to_string();
+        }
+
+        match (self.stress(), self.needs_written_accent()) {
+            (Stress::Esdrujula | Stress::Sobresdrujula, _) => {
+                format!("Lleva tilde por ser {}.", self.stress())
+            }
+            (stress, true) => {
+                format!("Lleva tilde por ser {} terminada en «{}».", stress, last)
+            }
+            (stress, false) => {
+                format!("No lleva tilde por ser {} terminada en «{}».", stress, last)
+            }
+        }
+    }
+}
+
+/// Whether a word without written accent ending like `syllables` is llana:
+/// it ends in vowel, or in «n» or «s» after a vowel
+fn ends_like_llana(syllables: &[String]) -> bool {
+    let Some(last) = syllables.last() else {
+        return false;
+    };
+    let mut letters = last.chars().rev();
+    match letters.next() {
+        Some(c) if VOWELS.contains(c) => true,
+        Some('n' | 's') => letters.next().is_some_and(|c| VOWELS.contains(c)),
+        _ => false,
+    }
+}
+
+/// Line with the syllables of `lemma` to be added to its definition. The
+/// stress is only shown for single words.
+pub fn syllables_line(lemma: &str) -> Option<String> {
+    let words: Vec<&str> = lemma.split(',').next()?.split_whitespace().collect();
+
+    if let [word] = words[..] {
+        let syllabification = Syllabification::new(word)?;
+        Some(format!(
+            "🔤 {} ({})",
+            syllabification.syllables.join("-"),
+            syllabification.stress()
+        ))
+    } else {
+        let words = words
+            .iter()
+            .map(|word| syllabify(word).map(|syllables| syllables.join("-")))
+            .collect::<Option<Vec<String>>>()?;
+        Some(format!("🔤 {}", words.join(" ")))
+    }
+}
+
+/// Send the syllables, stress and accentuation of each word of `text`
+pub async fn send_syllables(bot: &DLEBot, chat_id: ChatId, text: &str) -> ResponseResult<()> {
+    if text.trim().is_empty() {
+        bot.send_message(
+            chat_id,
+            "Escribe la palabra después del comando, por ejemplo: <code>/silabas camión</code>\n\nPara que las definiciones terminen con la división en sílabas usa /ajustes.",
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let analyses = text
+        .split_whitespace()
+        .map(|word| match Syllabification::new(word) {
+            Some(syllabification) => format!(
+                "<b>{}</b>\n{} {}, la tónica es «{}». Es {}.\n{}",
+                syllabification.syllables.join("-"),
+                syllabification.syllables.len(),
+                if syllabification.syllables.len() == 1 {
+                    "sílaba"
+                } else {
+                    "sílabas"
+                },
+                syllabification.syllables[syllabification.tonic],
+                syllabification.stress(),
+                syllabification.accent_rule()
+            ),
+            None => format!("No sé dividir «{}» en sílabas.", escape(word)),
+        })
+        .collect::<Vec<String>>()
+        .join("\n\n");
+
+    bot.send_message(chat_id, analyses).await?;
+
+    Ok(())
+}
+
+#[test]
+fn test_syllabify() {
+    let cases = [
+        // Single consonants and vowels
+        ("a", "a"),
+        ("casa", "ca-sa"),
+        ("examen", "e-xa-men"),
+        ("amor", "a-mor"),
+        // Digraphs
+        ("coche", "co-che"),
+        ("calle", "ca-lle"),
+        ("perro", "pe-rro"),
+        ("queso", "que-so"),
+        ("guitarra", "gui-ta-rra"),
+        ("aguja", "a-gu-ja"),
+        // Consonant clusters
+        ("libro", "li-bro"),
+        ("hablar", "ha-blar"),
+        ("cuadro", "cua-dro"),
+        ("atleta", "at-le-ta"),
+        ("carta", "car-ta"),
+        ("instante", "ins-tan-te"),
+        ("compra", "com-pra"),
+        ("construir", "cons-truir"),
+        ("obstáculo", "obs-tá-cu-lo"),
+        ("abstracto", "abs-trac-to"),
+        ("deshacer", "des-ha-cer"),
+        ("transporte", "trans-por-te"),
+        // Diphthongs
+        ("cielo", "cie-lo"),
+        ("aire", "ai-re"),
+        ("ciudad", "ciu-dad"),
+        ("cuida", "cui-da"),
+        ("causa", "cau-sa"),
+        ("canción", "can-ción"),
+        ("pingüino", "pin-güi-no"),
+        ("rey", "rey"),
+        ("hoyo", "ho-yo"),
+        // Hiatus
+        ("poeta", "po-e-ta"),
+        ("leer", "le-er"),
+        ("caos", "ca-os"),
+        ("río", "rí-o"),
+        ("país", "pa-ís"),
+        ("baúl", "ba-úl"),
+        ("búho", "bú-ho"),
+        ("chiita", "chi-i-ta"),
+        // Triphthongs
+        ("buey", "buey"),
+        ("averiguáis", "a-ve-ri-guáis"),
+        ("limpiéis", "lim-piéis"),
+        ("miau", "miau"),
+    ];
+
+    for (word, expected) in cases {
+        assert_eq!(
+            syllabify(word)
+                .map(|syllables| syllables.join("-"))
+                .as_deref(),
+            Some(expected),
+            "{}",
+            word
+        );
+    }
+
+    assert_eq!(syllabify("pst"), None);
+    assert_eq!(syllabify("anti-"), None);
+    assert_eq!(syllabify(""), None);
+}
+
+#[test]
+fn test_stress() {
+    let cases = [
+        ("canción", 1, Stress::Aguda),
+        ("amor", 1, Stress::Aguda),
+        ("reloj", 1, Stress::Aguda),
+        ("virrey", 1, Stress::Aguda),
+        ("robots", 1, Stress::Aguda),
+        ("casa", 0, Stress::Llana),
+        ("examen", 1, Stress::Llana),
+        ("lunes", 0, Stress::Llana),
+        ("árbol", 0, Stress::Llana),
+        ("bíceps", 0, Stress::Llana),
+        ("río", 0, Stress::Llana),
+        ("cántaro", 0, Stress::Esdrujula),
+        ("murciélago", 1, Stress::Esdrujula),
+        ("dígaselo", 0, Stress::Sobresdrujula),
+        ("sol", 0, Stress::Aguda),
+    ];
+
+    for (word, tonic, stress) in cases {
+        let syllabification = Syllabification::new(word).unwrap();
+        assert_eq!(syllabification.tonic, tonic, "{}", word);
+        assert_eq!(syllabification.stress(), stress, "{}", word);
+    }
+}
+
+#[test]
+fn test_written_accent() {
+    let cases = [
+        ("canción", true),
+        ("café", true),
+        ("compás", true),
+        ("amor", false),
+        ("robots", false),
+        ("casa", false),
+        ("árbol", true),
+        ("bíceps", true),
+        ("lunes", false),
+        ("cántaro", true),
+        ("dígaselo", true),
+        ("río", true),
+        ("búho", true),
+        ("sol", false),
+        ("él", false),
+    ];
+
+    for (word, needed) in cases {
+        let syllabification = Syllabification::new(word).unwrap();
+        assert_eq!(syllabification.needs_written_accent(), needed, "{}", word);
+        assert_eq!(
+            syllabification.has_written_accent(),
+            needed || word == "él",
+            "{}",
+            word
+        );
+    }
+
+    assert_eq!(
+        Syllabification::new("canción").unwrap().accent_rule(),
+        "Lleva tilde por ser aguda terminada en «n»."
+    );
+    assert_eq!(
+        Syllabification::new("casa").unwrap().accent_rule(),
+        "No lleva tilde por ser llana terminada en «a»."
+    );
+    assert_eq!(
+        Syllabification::new("cántaro").unwrap().accent_rule(),
+        "Lleva tilde por ser esdrújula."
+    );
+    assert_eq!(
+        Syllabification::new("baúl").unwrap().accent_rule(),
+        "Lleva tilde para marcar el hiato."
+    );
+    assert_eq!(
+        Syllabification::new("él").unwrap().accent_rule(),
+        "Es monosílaba y lleva tilde diacrítica."
+    );
+}
+
+#[test]
+fn test_syllables_line() {
+    assert_eq!(
+        syllables_line("canción").as_deref(),
+        Some("🔤 can-ción (aguda)")
+    );
+    assert_eq!(
+        syllables_line("bueno, na").as_deref(),
+        Some("🔤 bue-no (llana)")
+    );
+    assert_eq!(
+        syllables_line("a bote pronto").as_deref(),
+        Some("🔤 a bo-te pron-to")
+    );
+    assert_eq!(syllables_line("-ción"), None);
+}
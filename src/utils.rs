@@ -6,8 +6,22 @@ use base64::{
 use teloxide::types::LinkPreviewOptions;
 
 pub const MAX_MASSAGE_LENGTH: usize = 4096;
+/// Maximum length of the caption of a photo
+pub const MAX_CAPTION_LENGTH: usize = 1024;
 pub const SUBS_CALLBACK_DATA: &str = "__subs";
 pub const DESUBS_CALLBACK_DATA: &str = "__desubs";
+pub const CONJUGATION_CALLBACK_PREFIX: &str = "__conj:";
+pub const SOURCE_CALLBACK_PREFIX: &str = "__src:";
+pub const SENSE_CALLBACK_PREFIX: &str = "__sense:";
+pub const FULL_DEFINITION_CALLBACK_PREFIX: &str = "__full:";
+pub const ABBREVIATIONS_CALLBACK_PREFIX: &str = "__abbr:";
+pub const RELATIONS_CALLBACK_PREFIX: &str = "__rel:";
+pub const PATTERN_CALLBACK_PREFIX: &str = "__pat:";
+pub const CHAT_SETTINGS_CALLBACK_PREFIX: &str = "__chat:";
+pub const WORD_PICK_CALLBACK_PREFIX: &str = "__pick:";
+pub const SETTINGS_CALLBACK_PREFIX: &str = "__set:";
+/// Maximum size in bytes of the data of a callback button
+pub const MAX_CALLBACK_DATA_LENGTH: usize = 64;
 const CUSTOM_ENGINE: engine::GeneralPurpose =
     engine::GeneralPurpose::new(&alphabet::URL_SAFE, general_purpose::NO_PAD);
 pub const DISABLED_LINK_PREVIEW: LinkPreviewOptions = LinkPreviewOptions {
@@ -18,6 +32,14 @@ pub const DISABLED_LINK_PREVIEW: LinkPreviewOptions = LinkPreviewOptions {
     show_above_text: false,
 };
 
+/// Largest index not greater than `index` that lies on a char boundary of `text`
+fn floor_char_boundary(text: &str, mut index: usize) -> usize {
+    while !text.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
 /// Split one string into multiple strings with a maximum length of `chars_per_string`.
 /// Splits by '\n', '. ' or ' ' in this priority.
 pub fn smart_split(text: &str, chars_per_string: usize) -> Vec<&str> {
@@ -25,18 +47,19 @@ pub fn smart_split(text: &str, chars_per_string: usize) -> Vec<&str> {
     let mut left: usize = 0;
 
     while text.len() - left >= chars_per_string {
-        if let Some(pos) = text[left..(left + chars_per_string)].rfind('\n') {
+        let right = floor_char_boundary(text, left + chars_per_string);
+        if let Some(pos) = text[left..right].rfind('\n') {
             result.push(&text[left..=(left + pos)]);
             left += pos + 1;
-        } else if let Some(pos) = text[left..(left + chars_per_string)].rfind(". ") {
+        } else if let Some(pos) = text[left..right].rfind(". ") {
             result.push(&text[left..=(left + pos)]);
             left += pos + 1;
-        } else if let Some(pos) = text[left..(left + chars_per_string)].rfind(' ') {
+        } else if let Some(pos) = text[left..right].rfind(' ') {
             result.push(&text[left..=(left + pos)]);
             left += pos + 1;
         } else {
-            result.push(&text[left..(left + chars_per_string)]);
-            left += chars_per_string;
+            result.push(&text[left..right]);
+            left = right;
         }
     }
     result.push(&text[left..]);
@@ -81,6 +104,54 @@ pub fn split_by_whitespace(text: &str, chars_per_string: usize) -> Vec<&str> {
     result
 }
 
+/// Lowercase `text` and remove its written accents, keeping the «ñ».
+/// Same as the `unaccent_lemma` function of the database.
+pub fn unaccent(text: &str) -> String {
+    text.to_lowercase()
+        .chars()
+        .map(|c| match c {
+            'á' | 'à' | 'ä' | 'â' => 'a',
+            'é' | 'è' | 'ë' | 'ê' => 'e',
+            'í' | 'ì' | 'ï' | 'î' => 'i',
+            'ó' | 'ò' | 'ö' | 'ô' => 'o',
+            'ú' | 'ù' | 'ü' | 'û' => 'u',
+            _ => c,
+        })
+        .collect()
+}
+
+/// URL of the page of `lemma` in a dictionary website whose URL is
+/// `template` with `{}` in place of the lemma
+pub fn dictionary_url(template: &str, lemma: &str) -> Option<reqwest::Url> {
+    let encoded: String = lemma
+        .bytes()
+        .map(|byte| {
+            if byte.is_ascii_alphanumeric() || b"-._~".contains(&byte) {
+                (byte as char).to_string()
+            } else {
+                format!("%{:02X}", byte)
+            }
+        })
+        .collect();
+
+    reqwest::Url::parse(&template.replace("{}", &encoded)).ok()
+}
+
+/// HTML link to `lemma` that opens its definition in a private chat with the bot
+pub fn lemma_link(bot_username: &str, lemma: &str) -> String {
+    lemma_link_with_text(bot_username, lemma, lemma)
+}
+
+/// Same as `lemma_link` but showing `text` instead of the lemma
+pub fn lemma_link_with_text(bot_username: &str, lemma: &str, text: &str) -> String {
+    format!(
+        r#"<a href="https://t.me/{}?start={}">{}</a>"#,
+        bot_username,
+        base64_encode(lemma.to_string()),
+        text
+    )
+}
+
 pub fn base64_encode(text: String) -> String {
     CUSTOM_ENGINE.encode(text)
 }
@@ -109,3 +180,21 @@ fn test_smart_split() {
         ]
     );
 }
+
+#[test]
+fn test_smart_split_multibyte() {
+    let text = "áéíóú";
+    assert_eq!(smart_split(text, 3), ["á", "é", "í", "ó", "ú"]);
+}
+
+#[test]
+fn test_dictionary_url() {
+    assert_eq!(
+        dictionary_url("https://dle.rae.es/{}", "ñu").map(String::from),
+        Some("https://dle.rae.es/%C3%B1u".to_string())
+    );
+    assert_eq!(
+        dictionary_url("https://example.com/?q={}&x=1", "a b?c#d&e").map(String::from),
+        Some("https://example.com/?q=a%20b%3Fc%23d%26e&x=1".to_string())
+    );
+}
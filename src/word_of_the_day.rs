@@ -1,72 +1,297 @@
 use ::teloxide::prelude::*;
-use chrono::{offset::Local, Duration, Timelike};
+use chrono::{DateTime, Duration, NaiveDate, NaiveTime, TimeZone, Timelike, Utc};
+use chrono_tz::Tz;
 use tokio::time::{interval_at, Duration as StdDuration, Instant};
 
-use crate::{database::DatabaseHandler, image::send_image, DLEBot};
+use crate::{
+    database::{DatabaseHandler, DleModel, WotdTargetModel, DEFAULT_TIMEZONE},
+    delivery::timezone_or_default,
+    image::{send_image, Theme},
+    wotd_target::post_to_due_targets,
+    DLEBot,
+};
 
-const SECONDS_IN_A_DAY: u64 = 24 * 60 * 60;
+/// Name of the job that posted the word of the day in the groups and the
+/// channel in the `scheduled_job` table, before each chat had its own record
+const WOTD_JOB: &str = "word_of_the_day";
+/// Name of the job that makes `WOTD_CHANNEL_ID` the first target, run once
+const CHANNEL_SEED_JOB: &str = "wotd_channel_seed";
 
-async fn send_word_of_the_day(
-    db_handler: DatabaseHandler,
-    bot: DLEBot,
-    chat_id: ChatId,
-) -> ResponseResult<()> {
-    if let Ok(wotd) = db_handler.get_word_of_the_day().await {
-        send_image(wotd, bot, chat_id, true).await?;
+/// Time zone of the schedule of the word of the day, `SCHEDULE_TIMEZONE` or
+/// the default one if it isn't set or valid
+pub fn schedule_timezone() -> Tz {
+    timezone_or_default(&std::env::var("SCHEDULE_TIMEZONE").unwrap_or(DEFAULT_TIMEZONE.to_string()))
+}
+
+/// Get the word of the day of today in the time zone of the schedule
+pub async fn todays_word(db_handler: &DatabaseHandler, bot: &DLEBot) -> Option<DleModel> {
+    let today = Utc::now().with_timezone(&schedule_timezone()).date_naive();
+    word_of_the_day_for(db_handler, bot, today).await
+}
+
+/// Get the word of the day of `date`. If none was scheduled one is picked
+/// automatically and the admins are told which one.
+pub async fn word_of_the_day_for(
+    db_handler: &DatabaseHandler,
+    bot: &DLEBot,
+    date: NaiveDate,
+) -> Option<DleModel> {
+    if let Ok(wotd) = db_handler.get_word_of_the_day(date).await {
+        return Some(wotd);
     }
 
-    Ok(())
+    match db_handler.pick_word_of_the_day(date).await {
+        Ok(Some(lemma)) => {
+            log::info!("Picked «{}» as word of the day for {}", lemma, date);
+            let text = format!(
+                "🤖 No había palabra del día programada para el {}, elegí «{}». Puedes cambiarla con /setpdd.",
+                date.format("%d/%m/%Y"),
+                lemma
+            );
+            for admin in db_handler.get_admins().await {
+                if let Err(error) = bot.send_message(ChatId(admin), &text).await {
+                    log::warn!("Error while notifying admin {}: {:?}", admin, error);
+                }
+            }
+        }
+        Ok(None) => {}
+        Err(error) => {
+            log::error!("Error accessing the database: {:?}", error);
+        }
+    }
+
+    db_handler
+        .get_word_of_the_day(date)
+        .await
+        .map_err(|error| log::warn!("No word of the day for {}: {}", date, error))
+        .ok()
+}
+
+/// Instant of `time` of the local `date` in `timezone`. A time skipped when
+/// clocks go forward is moved an hour later, and of a time repeated when they
+/// go back the first one is taken.
+fn scheduled_at(date: NaiveDate, timezone: Tz, time: NaiveTime) -> Option<DateTime<Utc>> {
+    let local = date.and_time(time);
+
+    timezone
+        .from_local_datetime(&local)
+        .earliest()
+        .or_else(|| {
+            timezone
+                .from_local_datetime(&(local + Duration::hours(1)))
+                .earliest()
+        })
+        .map(|instant| instant.with_timezone(&Utc))
+}
+
+/// Last run scheduled at `time` in `timezone` not after `now`
+pub fn previous_run(now: DateTime<Utc>, timezone: Tz, time: NaiveTime) -> Option<DateTime<Utc>> {
+    let today = now.with_timezone(&timezone).date_naive();
+
+    [Some(today), today.pred_opt()]
+        .into_iter()
+        .flatten()
+        .filter_map(|date| scheduled_at(date, timezone, time))
+        .find(|run| *run <= now)
+}
+
+/// First run scheduled at `time` in `timezone` after `now`
+pub fn next_run(now: DateTime<Utc>, timezone: Tz, time: NaiveTime) -> Option<DateTime<Utc>> {
+    let today = now.with_timezone(&timezone).date_naive();
+
+    [Some(today), today.succ_opt()]
+        .into_iter()
+        .flatten()
+        .filter_map(|date| scheduled_at(date, timezone, time))
+        .find(|run| *run > now)
 }
 
-/// Schedule the execution of `send_word_of_the_day`
+/// Post the word of the day every day at `time` in `timezone` in the groups
+/// that want it, and in each target at its own time. If the bot wasn't
+/// running at that time or posting failed, it's posted later as long as it's
+/// within `grace` of the scheduled time. The posts are kept in the database
+/// so a chat never gets the word of a date twice.
 ///
 /// # Arguments
 ///
 /// * `db_handler` - Handler fot the database
 /// * `bot` - The bot
-/// * `hour` - Hour of the day to schedule the execution
-/// * `min` - Minute of the day to schedule the execution
+/// * `time` - Local time of the day to schedule the execution
+/// * `timezone` - Time zone of `time`
+/// * `grace` - How late a missed execution can still run
 ///
 pub async fn schedule_word_of_the_day(
     db_handler: DatabaseHandler,
     bot: DLEBot,
-    hour: u32,
-    min: u32,
+    time: NaiveTime,
+    timezone: Tz,
+    grace: Duration,
 ) {
-    let channel_id = std::env::var("WOTD_CHANNEL_ID")
-        .unwrap()
-        .parse::<i64>()
-        .unwrap();
-
-    let target_moment = Local::now()
-        .with_hour(hour)
-        .unwrap()
-        .with_minute(min)
-        .unwrap()
-        .with_second(0)
-        .unwrap();
-
-    let mut initial_delay = target_moment - Local::now();
-
-    if initial_delay < Duration::days(0) {
-        initial_delay = initial_delay + Duration::days(1);
+    // The channel used to be the only target, it's kept as the first one
+    if let Ok(channel_id) = std::env::var("WOTD_CHANNEL_ID") {
+        match channel_id.parse::<i64>() {
+            Ok(channel_id) => seed_target(&db_handler, channel_id, time, timezone).await,
+            Err(_) => log::error!("Invalid WOTD_CHANNEL_ID {:?}", channel_id),
+        }
     }
 
-    let initial_delay = StdDuration::from_secs(initial_delay.num_seconds().try_into().unwrap());
+    log::info!("Word of the day broadcast scheduled!");
 
-    let mut interval = interval_at(
-        Instant::now() + initial_delay,
-        StdDuration::from_secs(SECONDS_IN_A_DAY),
-    );
-
-    log::info!("Word of the days broadcast scheduled!");
+    let initial_delay = StdDuration::from_secs((60 - Utc::now().second()).into());
+    let mut interval = interval_at(Instant::now() + initial_delay, StdDuration::from_secs(60));
 
     loop {
+        let now = Utc::now();
+
+        if let Some(due) = previous_run(now, timezone, time) {
+            if now - due <= grace {
+                post_word_of_the_day(&db_handler, &bot, due, timezone).await;
+            }
+        }
+        post_to_due_targets(&db_handler, &bot, grace).await;
+
         interval.tick().await;
-        send_word_of_the_day(db_handler.clone(), bot.clone(), ChatId(channel_id))
-            .await
-            .unwrap_or_else(|error| {
-                log::warn!("Error while sending word of the day {:?}", error);
-            });
     }
 }
+
+/// Make `channel_id` a target at `time` in `timezone` the first time the bot
+/// starts with targets, carrying over the last time it was posted there. It's
+/// only done once so a channel removed with /deltarget doesn't come back.
+async fn seed_target(db_handler: &DatabaseHandler, channel_id: i64, time: NaiveTime, timezone: Tz) {
+    // Claiming a run due at the epoch only succeeds if it never ran
+    if !db_handler
+        .claim_scheduled_job(CHANNEL_SEED_JOB, DateTime::UNIX_EPOCH, Utc::now())
+        .await
+        || db_handler.get_wotd_target(channel_id).await.is_some()
+    {
+        return;
+    }
+
+    if let Some(last_run) = db_handler.get_last_run(WOTD_JOB).await {
+        db_handler
+            .claim_wotd_post(channel_id, last_run.with_timezone(&timezone).date_naive())
+            .await;
+    }
+
+    db_handler
+        .set_wotd_target(WotdTargetModel {
+            chat_id: channel_id,
+            delivery_time: time,
+            timezone: timezone.name().to_string(),
+            theme: Theme::Random.name().to_string(),
+            language: "es".to_string(),
+            enabled: true,
+        })
+        .await;
+    log::info!("Word of the day target {} created", channel_id);
+}
+
+/// Post the word of the day of the local date of `due` in `timezone` in the
+/// groups that want it and haven't got it yet
+async fn post_word_of_the_day(
+    db_handler: &DatabaseHandler,
+    bot: &DLEBot,
+    due: DateTime<Utc>,
+    timezone: Tz,
+) {
+    let date = due.with_timezone(&timezone).date_naive();
+    let mut wotd: Option<DleModel> = None;
+
+    for chat_id in db_handler.get_wotd_chats().await {
+        if !db_handler.claim_wotd_post(chat_id, date).await {
+            continue;
+        }
+
+        if Utc::now() - due > Duration::minutes(1) {
+            log::info!(
+                "Posting the word of the day in {} scheduled at {} late",
+                chat_id,
+                due
+            );
+        }
+
+        if wotd.is_none() {
+            wotd = word_of_the_day_for(db_handler, bot, date).await;
+        }
+        let sent = match &wotd {
+            Some(wotd) => send_image(wotd.clone(), bot.clone(), ChatId(chat_id), true)
+                .await
+                .map_err(|error| {
+                    log::warn!(
+                        "Error while sending word of the day to {}: {:?}",
+                        chat_id,
+                        error
+                    );
+                })
+                .unwrap_or(false),
+            None => false,
+        };
+
+        // Tried again next minute while within `grace`
+        if !sent {
+            db_handler.release_wotd_post(chat_id, date).await;
+        }
+    }
+}
+
+#[test]
+fn test_scheduled_at() {
+    let madrid: Tz = "Europe/Madrid".parse().unwrap();
+    let date = |text: &str| text.parse::<NaiveDate>().unwrap();
+    let time = |hour, min| NaiveTime::from_hms_opt(hour, min, 0).unwrap();
+    let utc = |text: &str| text.parse::<DateTime<Utc>>().ok();
+
+    assert_eq!(
+        scheduled_at(date("2026-07-01"), madrid, time(9, 0)),
+        utc("2026-07-01T07:00:00Z")
+    );
+    assert_eq!(
+        scheduled_at(date("2026-12-01"), madrid, time(9, 0)),
+        utc("2026-12-01T08:00:00Z")
+    );
+    // Skipped when clocks go forward
+    assert_eq!(
+        scheduled_at(date("2026-03-29"), madrid, time(2, 30)),
+        utc("2026-03-29T01:30:00Z")
+    );
+    // Repeated when clocks go back
+    assert_eq!(
+        scheduled_at(date("2026-10-25"), madrid, time(2, 30)),
+        utc("2026-10-25T00:30:00Z")
+    );
+}
+
+#[test]
+fn test_previous_and_next_run() {
+    let madrid: Tz = "Europe/Madrid".parse().unwrap();
+    let time = NaiveTime::from_hms_opt(9, 0, 0).unwrap();
+    let utc = |text: &str| text.parse::<DateTime<Utc>>().unwrap();
+
+    // The day clocks go back, runs are 25 hours apart instead of 24
+    let now = utc("2026-10-24T12:00:00Z");
+    assert_eq!(
+        previous_run(now, madrid, time),
+        Some(utc("2026-10-24T07:00:00Z"))
+    );
+    assert_eq!(
+        next_run(now, madrid, time),
+        Some(utc("2026-10-25T08:00:00Z"))
+    );
+
+    let now = utc("2026-10-25T07:30:00Z");
+    assert_eq!(
+        previous_run(now, madrid, time),
+        Some(utc("2026-10-24T07:00:00Z"))
+    );
+    assert_eq!(
+        next_run(now, madrid, time),
+        Some(utc("2026-10-25T08:00:00Z"))
+    );
+
+    let now = utc("2026-10-25T08:00:00Z");
+    assert_eq!(previous_run(now, madrid, time), Some(now));
+    assert_eq!(
+        next_run(now, madrid, time),
+        Some(utc("2026-10-26T08:00:00Z"))
+    );
+}
@@ -0,0 +1,89 @@
+use teloxide::{
+    prelude::*,
+    types::{InlineKeyboardButton, InlineKeyboardMarkup, Me},
+};
+
+use crate::{
+    database::DatabaseHandler,
+    handle_message::send_message,
+    spelling::tokenize,
+    utils::{MAX_CALLBACK_DATA_LENGTH, WORD_PICK_CALLBACK_PREFIX},
+    DLEBot,
+};
+
+/// Maximum number of words offered to pick from a message
+const MAX_CANDIDATES: usize = 24;
+
+/// Distinct words of `text` in lowercase and in order of appearance, leaving
+/// out single letters and those that don't fit in a button
+fn candidate_words(text: &str) -> Vec<String> {
+    let mut words: Vec<String> = vec![];
+
+    for token in tokenize(text).iter().filter(|token| token.word) {
+        let word = token.text.to_lowercase();
+        if word.chars().count() > 1
+            && WORD_PICK_CALLBACK_PREFIX.len() + word.len() <= MAX_CALLBACK_DATA_LENGTH
+            && !words.contains(&word)
+        {
+            words.push(word);
+        }
+        if words.len() == MAX_CANDIDATES {
+            break;
+        }
+    }
+
+    words
+}
+
+fn word_picker_keyboard(words: &[String]) -> InlineKeyboardMarkup {
+    let buttons: Vec<InlineKeyboardButton> = words
+        .iter()
+        .map(|word| {
+            InlineKeyboardButton::callback(word, format!("{}{}", WORD_PICK_CALLBACK_PREFIX, word))
+        })
+        .collect();
+
+    InlineKeyboardMarkup::new(buttons.chunks(3).map(|row| row.to_vec()))
+}
+
+/// Look up the word of `text` if it only has one, or let the user pick which
+/// of its words to look up
+pub async fn send_word_picker(
+    db_handler: DatabaseHandler,
+    bot: DLEBot,
+    msg: Message,
+    user_id: i64,
+    text: &str,
+    me: Me,
+) -> ResponseResult<()> {
+    let words = candidate_words(text);
+
+    match words.as_slice() {
+        [] => {
+            bot.send_message(
+                msg.chat.id,
+                "No encontré palabras que buscar en ese mensaje.",
+            )
+            .await?;
+        }
+        [word] => {
+            send_message(db_handler, bot, msg, user_id, word, me).await?;
+        }
+        _ => {
+            bot.send_message(msg.chat.id, "¿Qué palabra del mensaje quieres buscar?")
+                .reply_markup(word_picker_keyboard(&words))
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_candidate_words() {
+    assert_eq!(
+        candidate_words("¡Qué día! Y el día de ayer, 5 veces «Día»."),
+        ["qué", "día", "el", "de", "ayer", "veces"]
+    );
+    assert_eq!(candidate_words("... 123 y"), Vec::<String>::new());
+}
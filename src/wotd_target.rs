@@ -0,0 +1,336 @@
+use chrono::{DateTime, Duration, NaiveTime, Utc};
+use chrono_tz::Tz;
+use teloxide::prelude::*;
+
+use crate::{
+    database::{DatabaseHandler, WotdTargetModel, DEFAULT_TIMEZONE},
+    delivery::{parse_delivery_settings, timezone_or_default},
+    image::{send_themed_image, Theme},
+    word_of_the_day::{next_run, previous_run, word_of_the_day_for},
+    DLEBot,
+};
+
+/// Languages of the hashtag before the definition in the caption of the word
+/// of the day. Only the hashtag changes, the definition is always the Spanish
+/// one. The first one is the default.
+const CAPTION_LANGUAGES: [(&str, &str); 2] =
+    [("es", "📖 #PalabraDelDía |"), ("en", "📖 #WordOfTheDay |")];
+
+const SET_TARGET_USAGE: &str = "Uso: <code>/settarget &lt;chat_id&gt; &lt;ajuste&gt; &lt;valor&gt;</code>\n\n<b>hora</b>: <code>8:30</code>\n<b>zona</b>: <code>America/Havana</code>\n<b>tema</b>: <code>random</code>, <code>light</code> o <code>dark</code>\n<b>idioma</b> (solo del hashtag): <code>es</code> o <code>en</code>\n<b>activo</b>: <code>sí</code> o <code>no</code>";
+
+/// A change to the settings of a target made with `/settarget`
+#[derive(Debug, PartialEq)]
+enum TargetChange {
+    Time(NaiveTime),
+    Timezone(Tz),
+    Theme(Theme),
+    Language(&'static str),
+    Enabled(bool),
+}
+
+impl TargetChange {
+    fn apply(self, target: &mut WotdTargetModel) {
+        match self {
+            TargetChange::Time(time) => target.delivery_time = time,
+            TargetChange::Timezone(timezone) => target.timezone = timezone.name().to_string(),
+            TargetChange::Theme(theme) => target.theme = theme.name().to_string(),
+            TargetChange::Language(language) => target.language = language.to_string(),
+            TargetChange::Enabled(enabled) => target.enabled = enabled,
+        }
+    }
+}
+
+/// Text before the definition in the caption of the word of the day in
+/// `language`
+fn caption_prefix(language: &str) -> &'static str {
+    CAPTION_LANGUAGES
+        .iter()
+        .find(|(code, _)| *code == language)
+        .unwrap_or(&CAPTION_LANGUAGES[0])
+        .1
+}
+
+/// Parse the arguments of `/settarget`: the chat id, the setting and its
+/// new value
+fn parse_target_change(text: &str) -> Option<(i64, TargetChange)> {
+    let mut args = text.split_whitespace();
+
+    let chat_id = args.next()?.parse().ok()?;
+    let setting = args.next()?;
+    let value = args.next()?;
+    if args.next().is_some() {
+        return None;
+    }
+
+    let change = match setting.to_lowercase().as_str() {
+        "hora" => TargetChange::Time(parse_delivery_settings(value)?.0),
+        "zona" => TargetChange::Timezone(value.parse().ok()?),
+        "tema" => TargetChange::Theme(Theme::from_name(&value.to_lowercase())?),
+        "idioma" => TargetChange::Language(
+            CAPTION_LANGUAGES
+                .iter()
+                .find(|(code, _)| code.eq_ignore_ascii_case(value))?
+                .0,
+        ),
+        "activo" => match value.to_lowercase().as_str() {
+            "sí" | "si" => TargetChange::Enabled(true),
+            "no" => TargetChange::Enabled(false),
+            _ => return None,
+        },
+        _ => return None,
+    };
+
+    Some((chat_id, change))
+}
+
+fn describe_target(target: &WotdTargetModel, now: DateTime<Utc>) -> String {
+    let timezone = timezone_or_default(&target.timezone);
+    let next = if target.enabled {
+        next_run(now, timezone, target.delivery_time)
+            .map(|run| {
+                run.with_timezone(&timezone)
+                    .format("%d/%m/%Y %H:%M")
+                    .to_string()
+            })
+            .unwrap_or_default()
+    } else {
+        "desactivado".to_string()
+    };
+
+    format!(
+        "<code>{}</code>: {} ({}), tema {}, idioma {}. Siguiente: {}",
+        target.chat_id,
+        target.delivery_time.format("%H:%M"),
+        target.timezone,
+        target.theme,
+        target.language,
+        next
+    )
+}
+
+/// List the chats where the word of the day is posted on their own schedule
+pub async fn send_targets(
+    db_handler: &DatabaseHandler,
+    bot: &DLEBot,
+    chat_id: ChatId,
+) -> ResponseResult<()> {
+    let now = Utc::now();
+    let targets = db_handler.get_wotd_targets().await;
+
+    let text = if targets.is_empty() {
+        "No hay canales para la palabra del día. Añade uno con <code>/addtarget &lt;chat_id&gt; &lt;hora&gt; [zona]</code>".to_string()
+    } else {
+        targets
+            .iter()
+            .map(|target| describe_target(target, now))
+            .collect::<Vec<String>>()
+            .join("\n")
+    };
+    bot.send_message(chat_id, text).await?;
+
+    Ok(())
+}
+
+/// Start posting the word of the day in a chat at the given time
+pub async fn add_target(
+    db_handler: &DatabaseHandler,
+    bot: &DLEBot,
+    chat_id: ChatId,
+    text: &str,
+) -> ResponseResult<()> {
+    let (target_id, settings) = text.trim().split_once(' ').unwrap_or((text.trim(), ""));
+
+    let (Ok(target_id), Some((time, timezone))) =
+        (target_id.parse::<i64>(), parse_delivery_settings(settings))
+    else {
+        bot.send_message(
+            chat_id,
+            "Uso: <code>/addtarget &lt;chat_id&gt; &lt;hora&gt; [zona]</code>, por ejemplo: <code>/addtarget -1001234567890 8:30 America/Havana</code>",
+        )
+        .await?;
+        return Ok(());
+    };
+
+    let target = WotdTargetModel {
+        chat_id: target_id,
+        delivery_time: time,
+        timezone: timezone.map_or(DEFAULT_TIMEZONE.to_string(), |timezone| {
+            timezone.name().to_string()
+        }),
+        theme: Theme::Random.name().to_string(),
+        language: CAPTION_LANGUAGES[0].0.to_string(),
+        enabled: true,
+    };
+
+    // A new target starts with its next run, not with the one that just passed
+    let now = Utc::now();
+    let timezone = timezone_or_default(&target.timezone);
+    if let Some(due) = previous_run(now, timezone, time) {
+        db_handler
+            .claim_wotd_post(target_id, due.with_timezone(&timezone).date_naive())
+            .await;
+    }
+
+    let description = describe_target(&target, now);
+    db_handler.set_wotd_target(target).await;
+    bot.send_message(chat_id, format!("✅ {}", description))
+        .await?;
+
+    Ok(())
+}
+
+/// Change a setting of a chat where the word of the day is posted
+pub async fn set_target(
+    db_handler: &DatabaseHandler,
+    bot: &DLEBot,
+    chat_id: ChatId,
+    text: &str,
+) -> ResponseResult<()> {
+    let Some((target_id, change)) = parse_target_change(text) else {
+        bot.send_message(chat_id, SET_TARGET_USAGE).await?;
+        return Ok(());
+    };
+
+    let Some(mut target) = db_handler.get_wotd_target(target_id).await else {
+        bot.send_message(
+            chat_id,
+            format!(
+                "<code>{}</code> no es un canal de la palabra del día.",
+                target_id
+            ),
+        )
+        .await?;
+        return Ok(());
+    };
+
+    // The posts are recorded by date, so a new time or time zone doesn't
+    // post again a date that was already posted
+    change.apply(&mut target);
+
+    let description = describe_target(&target, Utc::now());
+    db_handler.set_wotd_target(target).await;
+    bot.send_message(chat_id, format!("✅ {}", description))
+        .await?;
+
+    Ok(())
+}
+
+/// Stop posting the word of the day in a chat
+pub async fn delete_target(
+    db_handler: &DatabaseHandler,
+    bot: &DLEBot,
+    chat_id: ChatId,
+    text: &str,
+) -> ResponseResult<()> {
+    let text = match text.trim().parse::<i64>() {
+        Ok(target_id) if db_handler.remove_wotd_target(target_id).await => {
+            format!("✅ Ya no se publicará en <code>{}</code>.", target_id)
+        }
+        Ok(target_id) => format!(
+            "<code>{}</code> no es un canal de la palabra del día.",
+            target_id
+        ),
+        Err(_) => "Uso: <code>/deltarget &lt;chat_id&gt;</code>".to_string(),
+    };
+    bot.send_message(chat_id, text).await?;
+
+    Ok(())
+}
+
+/// Post the word of the day of its local date in every enabled target whose
+/// time has come and that hasn't got the word of that date yet, as long as
+/// it's within `grace` of its time
+pub async fn post_to_due_targets(db_handler: &DatabaseHandler, bot: &DLEBot, grace: Duration) {
+    let now = Utc::now();
+
+    for target in db_handler.get_wotd_targets().await {
+        if !target.enabled {
+            continue;
+        }
+
+        let timezone = timezone_or_default(&target.timezone);
+        let Some(due) = previous_run(now, timezone, target.delivery_time) else {
+            continue;
+        };
+        if now - due > grace {
+            continue;
+        }
+
+        let date = due.with_timezone(&timezone).date_naive();
+        if !db_handler.claim_wotd_post(target.chat_id, date).await {
+            continue;
+        }
+
+        if now - due > Duration::minutes(1) {
+            log::info!(
+                "Posting the word of the day in {} scheduled at {} late",
+                target.chat_id,
+                due
+            );
+        }
+
+        let sent = match word_of_the_day_for(db_handler, bot, date).await {
+            Some(wotd) => send_themed_image(
+                wotd,
+                bot.clone(),
+                ChatId(target.chat_id),
+                caption_prefix(&target.language),
+                date,
+                Theme::from_name(&target.theme).unwrap_or(Theme::Random),
+            )
+            .await
+            .map_err(|error| {
+                log::warn!(
+                    "Error while sending word of the day to {}: {:?}",
+                    target.chat_id,
+                    error
+                );
+            })
+            .unwrap_or(false),
+            None => false,
+        };
+
+        // Tried again next minute while within `grace`
+        if !sent {
+            db_handler.release_wotd_post(target.chat_id, date).await;
+        }
+    }
+}
+
+#[test]
+fn test_parse_target_change() {
+    assert_eq!(
+        parse_target_change("-1001234 hora 8:30"),
+        Some((
+            -1001234,
+            TargetChange::Time(NaiveTime::from_hms_opt(8, 30, 0).unwrap())
+        ))
+    );
+    assert_eq!(
+        parse_target_change("-1001234 zona America/Havana"),
+        Some((-1001234, TargetChange::Timezone(Tz::America__Havana)))
+    );
+    assert_eq!(
+        parse_target_change("42 Tema Dark"),
+        Some((42, TargetChange::Theme(Theme::Dark)))
+    );
+    assert_eq!(
+        parse_target_change("42 idioma EN"),
+        Some((42, TargetChange::Language("en")))
+    );
+    assert_eq!(
+        parse_target_change("42 activo no"),
+        Some((42, TargetChange::Enabled(false)))
+    );
+    assert_eq!(parse_target_change("42 hora 8:30 UTC"), None);
+    assert_eq!(parse_target_change("42 idioma fr"), None);
+    assert_eq!(parse_target_change("canal tema dark"), None);
+    assert_eq!(parse_target_change("42 tema"), None);
+}
+
+#[test]
+fn test_caption_prefix() {
+    assert_eq!(caption_prefix("en"), "📖 #WordOfTheDay |");
+    assert_eq!(caption_prefix("es"), "📖 #PalabraDelDía |");
+    assert_eq!(caption_prefix("xx"), "📖 #PalabraDelDía |");
+}